@@ -0,0 +1,199 @@
+use error_chain::bail;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use log::debug;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use zip::write::{FileOptions, ZipWriter};
+
+use crate::errors::ErrorKind;
+use crate::models::ProjectArtifactCompressionFormat;
+use crate::CromResult;
+
+/// Build an archive at `dest` containing `files`.
+///
+/// Each entry is a pair of the name inside the archive and the path on
+/// disk. Directories are added recursively under their name.
+pub fn create_archive(
+    format: &ProjectArtifactCompressionFormat,
+    dest: &Path,
+    files: &[(String, PathBuf)],
+) -> CromResult<()> {
+    debug!("Creating {:?} archive at {:?}", format, dest);
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match format {
+        ProjectArtifactCompressionFormat::Tgz => create_tgz(dest, files),
+        ProjectArtifactCompressionFormat::Zip => create_zip(dest, files),
+    }
+}
+
+fn create_tgz(dest: &Path, files: &[(String, PathBuf)]) -> CromResult<()> {
+    let encoder = GzEncoder::new(File::create(dest)?, Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+
+    for (name, path) in files {
+        let result = if path.is_dir() {
+            builder.append_dir_all(name, path)
+        } else {
+            builder.append_path_with_name(path, name)
+        };
+
+        if let Err(e) = result {
+            bail!(ErrorKind::CompressionError(format!("{:?}: {}", path, e)));
+        }
+    }
+
+    if let Err(e) = builder.into_inner().and_then(|encoder| encoder.finish()) {
+        bail!(ErrorKind::CompressionError(e.to_string()));
+    }
+
+    Ok(())
+}
+
+fn create_zip(dest: &Path, files: &[(String, PathBuf)]) -> CromResult<()> {
+    let mut writer = ZipWriter::new(File::create(dest)?);
+
+    for (name, path) in files {
+        add_to_zip(&mut writer, name, path)?;
+    }
+
+    if let Err(e) = writer.finish() {
+        bail!(ErrorKind::CompressionError(e.to_string()));
+    }
+
+    Ok(())
+}
+
+fn add_to_zip(writer: &mut ZipWriter<File>, name: &str, path: &Path) -> CromResult<()> {
+    let metadata = fs::metadata(path)?;
+    let options = zip_options(&metadata);
+
+    if metadata.is_dir() {
+        if let Err(e) = writer.add_directory(name, options) {
+            bail!(ErrorKind::CompressionError(e.to_string()));
+        }
+
+        let mut children: Vec<PathBuf> = fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        children.sort();
+
+        for child in children {
+            let child_name = match child.file_name() {
+                Some(child_name) => format!("{}/{}", name, child_name.to_string_lossy()),
+                None => continue,
+            };
+            add_to_zip(writer, &child_name, &child)?;
+        }
+    } else {
+        if let Err(e) = writer.start_file(name, options) {
+            bail!(ErrorKind::CompressionError(e.to_string()));
+        }
+
+        io::copy(&mut File::open(path)?, writer)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn zip_options(metadata: &fs::Metadata) -> FileOptions {
+    use std::os::unix::fs::PermissionsExt;
+
+    FileOptions::default().unix_permissions(metadata.permissions().mode())
+}
+
+#[cfg(not(unix))]
+fn zip_options(_metadata: &fs::Metadata) -> FileOptions {
+    FileOptions::default()
+}
+
+#[cfg(test)]
+fn create_test_files(dir: &Path) -> Vec<(String, PathBuf)> {
+    fs::create_dir_all(dir.join("target/docs")).unwrap();
+    fs::write(dir.join("target/crom"), "crom binary").unwrap();
+    fs::write(dir.join("target/docs/README.md"), "# Crom").unwrap();
+
+    vec![
+        (s!("bin/crom"), dir.join("target/crom")),
+        (s!("docs"), dir.join("target/docs")),
+    ]
+}
+
+#[test]
+fn test_create_tgz() {
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    let dir = tempfile::tempdir().unwrap();
+    let files = create_test_files(dir.path());
+    let dest = dir.path().join("out/linux.tar.gz");
+
+    create_archive(&ProjectArtifactCompressionFormat::Tgz, &dest, &files).unwrap();
+
+    let mut archive = tar::Archive::new(GzDecoder::new(File::open(dest).unwrap()));
+    let mut entries: Vec<(String, String)> = archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().to_string_lossy().to_string();
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            (name, contents)
+        })
+        .filter(|(name, _)| !name.ends_with('/') && name != "docs")
+        .collect();
+    entries.sort();
+
+    assert_eq!(
+        vec![
+            (s!("bin/crom"), s!("crom binary")),
+            (s!("docs/README.md"), s!("# Crom")),
+        ],
+        entries
+    );
+}
+
+#[test]
+fn test_create_tgz_missing_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let files = vec![(s!("bin/crom"), dir.path().join("target/crom"))];
+    let dest = dir.path().join("linux.tar.gz");
+
+    let err = create_archive(&ProjectArtifactCompressionFormat::Tgz, &dest, &files).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::CompressionError(_)));
+}
+
+#[test]
+fn test_create_zip() {
+    use std::io::Read;
+
+    let dir = tempfile::tempdir().unwrap();
+    let files = create_test_files(dir.path());
+    let dest = dir.path().join("linux.zip");
+
+    create_archive(&ProjectArtifactCompressionFormat::Zip, &dest, &files).unwrap();
+
+    let mut archive = zip::ZipArchive::new(File::open(dest).unwrap()).unwrap();
+    let mut contents = String::new();
+    archive
+        .by_name("bin/crom")
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!("crom binary", contents);
+
+    contents.clear();
+    archive
+        .by_name("docs/README.md")
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!("# Crom", contents);
+}
@@ -1,6 +1,8 @@
 use clap::{ArgEnum, ArgGroup, Parser};
 use log::LevelFilter;
 
+use crate::version::VersionBump;
+
 #[derive(Parser, Debug)]
 #[clap(group = ArgGroup::new("logging"))]
 pub struct LoggingOpts {
@@ -36,8 +38,38 @@ impl LoggingOpts {
 pub enum VersionRequest {
     Custom(String),
     Latest,
-    NextRelease,
-    PreRelease,
+    NextRelease(VersionBump),
+    PreRelease(VersionBump),
+    Describe,
+    Current { bump: VersionBump, dirty: bool },
+}
+
+/// Options for repos with several components.
+#[derive(Parser, Debug)]
+pub struct ComponentOpts {
+    /// Only use this `[component.<name>]`, by default every component is used.
+    #[clap(long, global(true))]
+    pub component: Option<String>,
+}
+
+/// Options for how the git history is read.
+#[derive(Parser, Debug, Clone)]
+pub struct HistoryOpts {
+    /// Fetch tags from the remote before computing versions, along with the
+    /// full history when the repo is a shallow clone.
+    #[clap(long, global(true))]
+    pub fetch: bool,
+
+    /// The git remote tags are fetched from.
+    #[clap(long, global(true), default_value = "origin")]
+    pub fetch_remote: String,
+
+    /// Allow the repo to have no tags, e.g. before the first release.
+    ///
+    /// Without this, crom fails when a repo with a remote has no tags, since
+    /// the tags were most likely not fetched.
+    #[clap(long, global(true))]
+    pub allow_no_tags: bool,
 }
 
 #[derive(Parser, Debug)]
@@ -55,6 +87,12 @@ pub enum SubCommand {
     Get(GetArgs),
     #[clap(alias = "write")]
     WriteVersion(WriteArgs),
+    Tag(TagArgs),
+    Release(ReleaseArgs),
+    Upload(UploadArgs),
+    Artifact(ArtifactArgs),
+    Changed(ChangedArgs),
+    Env(EnvArgs),
     #[clap(name = "util", alias = "utility", alias = "utilities")]
     Utility(UtilityArgs),
     #[cfg(feature = "gh-cli")]
@@ -83,6 +121,31 @@ pub struct InitArgs {
 pub struct GetArgs {
     #[clap(subcommand)]
     pub sub_command: GetSubCommand,
+
+    /// How the version should be printed.
+    ///
+    /// `json` includes the pattern, HEAD, the latest tag and the state of
+    /// the repo along with the version.
+    #[clap(long, global(true), arg_enum, default_value = "text")]
+    pub format: OutputFormat,
+
+    /// Use the tags on this git remote, without fetching them.
+    ///
+    /// Local tags are ignored, so stale tags can't change the version.
+    #[clap(long, global(true))]
+    pub remote: Option<String>,
+
+    #[clap(flatten)]
+    pub component_opts: ComponentOpts,
+
+    #[clap(flatten)]
+    pub history_opts: HistoryOpts,
+}
+
+#[derive(ArgEnum, Debug, Clone, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
 }
 
 #[derive(Parser, Debug)]
@@ -100,7 +163,7 @@ pub enum GetSubCommand {
     /// If you use the 'atomic' bumper, this will always return the
     /// next version.
     #[clap(alias = "snapshot-version")]
-    PreRelease,
+    PreRelease(BumpArgs),
 
     /// Get the next version of the repository
     ///
@@ -110,17 +173,70 @@ pub enum GetSubCommand {
     /// If you use the 'atomic' bumper, the version will be the next
     /// integer.
     #[clap(alias = "next-release-version")]
-    NextRelease,
+    NextRelease(NextReleaseArgs),
+
+    /// Describe HEAD relative to the latest version, like `git describe --tags`.
+    ///
+    /// When HEAD is tagged, the tag is returned. Otherwise the number of
+    /// commits since the tag and the short sha are added, e.g. `v0.1.4-3-g0cc81e3`.
+    Describe,
+
+    /// Get the version of the current checkout.
+    ///
+    /// When HEAD is tagged with a version that version is returned,
+    /// otherwise the pre-release version.
+    Current(CurrentArgs),
 }
 
 impl GetSubCommand {
     pub fn make_version_request(&self) -> VersionRequest {
         match self {
             GetSubCommand::Latest => VersionRequest::Latest,
-            GetSubCommand::PreRelease => VersionRequest::PreRelease,
-            GetSubCommand::NextRelease => VersionRequest::NextRelease,
+            GetSubCommand::PreRelease(args) => VersionRequest::PreRelease(args.bump),
+            GetSubCommand::NextRelease(args) => VersionRequest::NextRelease(args.bump.bump),
+            GetSubCommand::Describe => VersionRequest::Describe,
+            GetSubCommand::Current(args) => VersionRequest::Current {
+                bump: args.bump.bump,
+                dirty: args.dirty,
+            },
         }
     }
+
+    pub fn if_changed(&self) -> bool {
+        matches!(self, GetSubCommand::NextRelease(args) if args.if_changed)
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct CurrentArgs {
+    #[clap(flatten)]
+    pub bump: BumpArgs,
+
+    /// Add a `dirty` marker when there are uncommitted changes.
+    #[clap(long)]
+    pub dirty: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct NextReleaseArgs {
+    #[clap(flatten)]
+    pub bump: BumpArgs,
+
+    /// Only print versions when files in `paths` changed since the latest
+    /// version. Exits with 1 when nothing changed.
+    #[clap(long)]
+    pub if_changed: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct BumpArgs {
+    /// Which counter in the pattern to increment.
+    ///
+    /// Counters are the `%d`s in the pattern, counted from the right, so
+    /// `patch` is always the last one. Counters to the right of the one
+    /// being bumped are reset to 0.
+    #[clap(long, arg_enum, default_value = "patch")]
+    pub bump: VersionBump,
 }
 
 /// Write version into defined sources.
@@ -131,6 +247,12 @@ impl GetSubCommand {
 pub struct WriteArgs {
     #[clap(subcommand)]
     pub sub_command: WriteSubCommand,
+
+    #[clap(flatten)]
+    pub component_opts: ComponentOpts,
+
+    #[clap(flatten)]
+    pub history_opts: HistoryOpts,
 }
 
 #[derive(Parser, Debug)]
@@ -143,12 +265,12 @@ pub enum WriteSubCommand {
     /// Write the pre-release version
     ///
     /// See [get pre-release] for how the value is computed.
-    PreRelease,
+    PreRelease(BumpArgs),
 
     /// Write the next-release version
     ///
     /// See [get next-release] for how the value is computed.
-    NextRelease,
+    NextRelease(BumpArgs),
 
     /// Write the custom version
     ///
@@ -160,8 +282,8 @@ impl WriteSubCommand {
     pub fn make_version_request(&self) -> VersionRequest {
         match self {
             WriteSubCommand::Custom(args) => VersionRequest::Custom(args.version.clone()),
-            WriteSubCommand::PreRelease => VersionRequest::PreRelease,
-            WriteSubCommand::NextRelease => VersionRequest::NextRelease,
+            WriteSubCommand::PreRelease(args) => VersionRequest::PreRelease(args.bump),
+            WriteSubCommand::NextRelease(args) => VersionRequest::NextRelease(args.bump),
             WriteSubCommand::Latest => VersionRequest::Latest,
         }
     }
@@ -173,6 +295,301 @@ pub struct WriteSubCommandArgsCustom {
     pub version: String,
 }
 
+/// Create a git tag for a version.
+///
+/// The tag is annotated, and the message is rendered from the
+/// `message-template` in the `.crom.toml` file.
+#[derive(Parser, Debug)]
+pub struct TagArgs {
+    #[clap(subcommand)]
+    pub sub_command: TagSubCommand,
+
+    /// Create the tag in the local repo.
+    ///
+    /// This is the default when no other destination is given.
+    #[clap(long, global(true))]
+    pub local: bool,
+
+    /// Push the tag to the git remote after it's created.
+    #[clap(long, global(true))]
+    pub push: bool,
+
+    /// Create the tag on GitHub, by creating a release.
+    #[clap(long, global(true))]
+    pub github: bool,
+
+    /// The git remote to push to.
+    #[clap(long, global(true), default_value = "origin")]
+    pub remote: String,
+
+    #[clap(flatten)]
+    pub github_opts: GitHubOpts,
+
+    #[clap(flatten)]
+    pub component_opts: ComponentOpts,
+
+    #[clap(flatten)]
+    pub history_opts: HistoryOpts,
+}
+
+#[derive(Parser, Debug)]
+pub enum TagSubCommand {
+    /// Tag the latest version
+    ///
+    /// See [get latest] for how the value is computed.
+    Latest,
+
+    /// Tag the pre-release version
+    ///
+    /// See [get pre-release] for how the value is computed.
+    PreRelease(BumpArgs),
+
+    /// Tag the next-release version
+    ///
+    /// See [get next-release] for how the value is computed.
+    NextRelease(BumpArgs),
+
+    /// Tag the custom version
+    ///
+    /// Version is required arg so crom knows what to tag.
+    Custom(WriteSubCommandArgsCustom),
+}
+
+impl TagSubCommand {
+    pub fn make_version_request(&self) -> VersionRequest {
+        match self {
+            TagSubCommand::Custom(args) => VersionRequest::Custom(args.version.clone()),
+            TagSubCommand::PreRelease(args) => VersionRequest::PreRelease(args.bump),
+            TagSubCommand::NextRelease(args) => VersionRequest::NextRelease(args.bump),
+            TagSubCommand::Latest => VersionRequest::Latest,
+        }
+    }
+}
+
+/// Options used when talking to GitHub.
+#[derive(Parser, Debug)]
+pub struct GitHubOpts {
+    /// Token used to authenticate with GitHub.
+    #[clap(long, global(true), env = "GITHUB_TOKEN", hide_env_values = true)]
+    pub github_token: Option<String>,
+
+    /// Base url of the GitHub API, change this for GitHub Enterprise.
+    #[clap(
+        long,
+        global(true),
+        env = "GITHUB_API_URL",
+        default_value = "https://api.github.com"
+    )]
+    pub github_api: String,
+}
+
+/// Manage releases on GitHub.
+#[derive(Parser, Debug)]
+pub struct ReleaseArgs {
+    #[clap(subcommand)]
+    pub sub_command: ReleaseSubCommand,
+}
+
+#[derive(Parser, Debug)]
+pub enum ReleaseSubCommand {
+    /// Create a GitHub release for a version.
+    ///
+    /// The owner and repo are taken from the git remote, and the
+    /// release notes from `message-template`.
+    Create(ReleaseCreateArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ReleaseCreateArgs {
+    #[clap(subcommand)]
+    pub sub_command: TagSubCommand,
+
+    /// The git remote used to find the GitHub repo.
+    #[clap(long, global(true), default_value = "origin")]
+    pub remote: String,
+
+    #[clap(flatten)]
+    pub github_opts: GitHubOpts,
+
+    #[clap(flatten)]
+    pub component_opts: ComponentOpts,
+
+    #[clap(flatten)]
+    pub history_opts: HistoryOpts,
+}
+
+/// Upload artifacts for a version.
+///
+/// Artifacts are defined in the `[artifact.<name>]` sections of the
+/// `.crom.toml` file.
+#[derive(Parser, Debug)]
+pub struct UploadArgs {
+    #[clap(subcommand)]
+    pub sub_command: UploadSubCommand,
+
+    /// The git remote used to find the GitHub repo.
+    #[clap(long, global(true), default_value = "origin")]
+    pub remote: String,
+
+    #[clap(flatten)]
+    pub github_opts: GitHubOpts,
+
+    #[clap(flatten)]
+    pub component_opts: ComponentOpts,
+
+    #[clap(flatten)]
+    pub history_opts: HistoryOpts,
+}
+
+#[derive(Parser, Debug)]
+pub enum UploadSubCommand {
+    /// Upload artifacts to the latest version
+    ///
+    /// See [get latest] for how the value is computed.
+    Latest(UploadSubCommandArgs),
+
+    /// Upload artifacts to the pre-release version
+    ///
+    /// See [get pre-release] for how the value is computed.
+    PreRelease(UploadSubCommandBumpArgs),
+
+    /// Upload artifacts to the next-release version
+    ///
+    /// See [get next-release] for how the value is computed.
+    NextRelease(UploadSubCommandBumpArgs),
+
+    /// Upload artifacts to the custom version
+    Custom(UploadSubCommandArgsCustom),
+}
+
+impl UploadSubCommand {
+    pub fn make_version_request(&self) -> VersionRequest {
+        match self {
+            UploadSubCommand::Custom(args) => VersionRequest::Custom(args.version.clone()),
+            UploadSubCommand::PreRelease(args) => VersionRequest::PreRelease(args.bump.bump),
+            UploadSubCommand::NextRelease(args) => VersionRequest::NextRelease(args.bump.bump),
+            UploadSubCommand::Latest(_) => VersionRequest::Latest,
+        }
+    }
+
+    pub fn artifact_names(&self) -> &[String] {
+        match self {
+            UploadSubCommand::Custom(args) => &args.names,
+            UploadSubCommand::PreRelease(args) => &args.names,
+            UploadSubCommand::NextRelease(args) => &args.names,
+            UploadSubCommand::Latest(args) => &args.names,
+        }
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct UploadSubCommandArgs {
+    /// Names of the artifacts to upload.
+    #[clap(required = true)]
+    pub names: Vec<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct UploadSubCommandBumpArgs {
+    /// Names of the artifacts to upload.
+    #[clap(required = true)]
+    pub names: Vec<String>,
+
+    #[clap(flatten)]
+    pub bump: BumpArgs,
+}
+
+#[derive(Parser, Debug)]
+pub struct UploadSubCommandArgsCustom {
+    /// The custom version to upload to.
+    pub version: String,
+
+    /// Names of the artifacts to upload.
+    #[clap(required = true)]
+    pub names: Vec<String>,
+}
+
+/// Work with the artifacts defined in `.crom.toml`.
+#[derive(Parser, Debug)]
+pub struct ArtifactArgs {
+    #[clap(subcommand)]
+    pub sub_command: ArtifactSubCommand,
+
+    #[clap(flatten)]
+    pub component_opts: ComponentOpts,
+}
+
+#[derive(Parser, Debug)]
+pub enum ArtifactSubCommand {
+    /// Build the archive for an artifact
+    ///
+    /// The artifact must have a `compress` field, every entry in
+    /// `paths` will be included in the archive.
+    Pack(ArtifactPackArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct ArtifactPackArgs {
+    /// Names of the artifacts to pack.
+    #[clap(required = true)]
+    pub names: Vec<String>,
+
+    /// Directory the archives will be written to.
+    #[clap(long, default_value = ".")]
+    pub output_dir: String,
+}
+
+/// Check if files changed since the latest version.
+///
+/// Files are selected with `paths` in the `.crom.toml` file. Exits with 1
+/// when nothing changed, so a release can be skipped.
+#[derive(Parser, Debug)]
+pub struct ChangedArgs {
+    #[clap(flatten)]
+    pub component_opts: ComponentOpts,
+
+    #[clap(flatten)]
+    pub history_opts: HistoryOpts,
+}
+
+/// Write version variables for CI.
+///
+/// Writes `CROM_VERSION`, `CROM_NEXT_VERSION`, `CROM_LATEST_VERSION`,
+/// `CROM_SHA` and `CROM_IS_RELEASE`. When there is more than one
+/// component, the component name is added, e.g. `CROM_SERVER_VERSION`.
+#[derive(Parser, Debug)]
+pub struct EnvArgs {
+    /// Format to write, detected from the CI environment when not given.
+    #[clap(long, arg_enum)]
+    pub format: Option<EnvFormat>,
+
+    /// File to write to.
+    ///
+    /// Defaults to `$GITHUB_OUTPUT` for `github`, `crom.env` for `gitlab`
+    /// and stdout for `shell`.
+    #[clap(long)]
+    pub output: Option<String>,
+
+    #[clap(flatten)]
+    pub bump: BumpArgs,
+
+    #[clap(flatten)]
+    pub component_opts: ComponentOpts,
+
+    #[clap(flatten)]
+    pub history_opts: HistoryOpts,
+}
+
+#[derive(ArgEnum, Debug, Clone, PartialEq)]
+pub enum EnvFormat {
+    /// `name=value` lines appended to `$GITHUB_OUTPUT`
+    Github,
+    /// A dotenv report for GitLab
+    Gitlab,
+    /// `export` statements
+    Shell,
+}
+
 /// Utility that are useful during CI.
 #[derive(Parser, Debug)]
 pub struct UtilityArgs {
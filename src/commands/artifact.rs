@@ -0,0 +1,213 @@
+use async_trait::async_trait;
+use error_chain::bail;
+use log::info;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::archive;
+use crate::cli::{ArtifactArgs, ArtifactSubCommand};
+use crate::errors::ErrorKind;
+use crate::models::{Component, ProjectArtifactTarget, ProjectArtifactWrapper, ProjectArtifacts};
+use crate::CromResult;
+
+pub struct ArtifactCommand;
+
+#[async_trait]
+impl super::CommandRunner<ArtifactArgs> for ArtifactCommand {
+    async fn run_command(args: ArtifactArgs) -> CromResult<i32> {
+        let component = args.component_opts.component;
+        match args.sub_command {
+            ArtifactSubCommand::Pack(args) => {
+                let (location, config) = crate::models::find_project_config().await?;
+                let components = config.components(component.as_deref())?;
+                check_artifact_names(&components.iter().collect::<Vec<_>>(), &args.names)?;
+
+                let mut artifacts = Vec::new();
+                for component in &components {
+                    let names = component.artifact_names(&args.names);
+                    artifacts.extend(find_artifacts(
+                        &component.root(&location),
+                        &component.artifact,
+                        &names,
+                    )?);
+                }
+
+                let output_dir = std::env::current_dir()?.join(args.output_dir);
+                for artifact in artifacts {
+                    if artifact.compress.is_none() {
+                        bail!(ErrorKind::CompressionError(format!(
+                            "Artifact {} does not define `compress`",
+                            artifact.name
+                        )));
+                    }
+
+                    for (_, path) in artifact.package(&output_dir)? {
+                        info!("Created {:?}", path);
+                    }
+                }
+
+                Ok(0)
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ResolvedArtifact {
+    pub name: String,
+    pub target: ProjectArtifactTarget,
+    pub compress: Option<ProjectArtifactWrapper>,
+    pub files: Vec<(String, PathBuf)>,
+}
+
+impl ResolvedArtifact {
+    /// The files that should be published for this artifact.
+    ///
+    /// When `compress` is set, the archive is built into `output_dir`
+    /// and is the only file returned.
+    pub fn package(&self, output_dir: &Path) -> CromResult<Vec<(String, PathBuf)>> {
+        match &self.compress {
+            None => Ok(self.files.clone()),
+            Some(wrapper) => {
+                let dest = output_dir.join(&wrapper.name);
+                archive::create_archive(&wrapper.format, &dest, &self.files)?;
+                Ok(vec![(wrapper.name.clone(), dest)])
+            }
+        }
+    }
+}
+
+/// Make sure every name is defined by at least one of the components.
+pub fn check_artifact_names(components: &[&Component], names: &[String]) -> CromResult<()> {
+    for name in names {
+        if !components.iter().any(|c| c.artifact.contains_key(name)) {
+            bail!(ErrorKind::ArtifactMissing(format!(
+                "{} (not defined in {})",
+                name,
+                crate::statics::CONFIG_FILE
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Look up the named artifacts, making sure every path exists before
+/// anything is uploaded.
+pub fn find_artifacts(
+    root: &Path,
+    artifacts: &HashMap<String, ProjectArtifacts>,
+    names: &[String],
+) -> CromResult<Vec<ResolvedArtifact>> {
+    let mut resolved = Vec::new();
+
+    for name in names {
+        let artifact = match artifacts.get(name) {
+            Some(artifact) => artifact,
+            None => bail!(ErrorKind::ArtifactMissing(format!(
+                "{} (not defined in {})",
+                name,
+                crate::statics::CONFIG_FILE
+            ))),
+        };
+
+        let mut files = Vec::new();
+        for (file_name, file_path) in &artifact.paths {
+            let path = root.join(file_path);
+            if !path.exists() {
+                bail!(ErrorKind::ArtifactMissing(format!(
+                    "{} ({:?})",
+                    file_name, path
+                )));
+            }
+
+            files.push((file_name.clone(), path));
+        }
+        files.sort();
+
+        resolved.push(ResolvedArtifact {
+            name: name.clone(),
+            target: artifact.target.clone(),
+            compress: artifact.compress.clone(),
+            files,
+        });
+    }
+
+    Ok(resolved)
+}
+
+#[test]
+fn test_find_artifacts() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("target/release")).unwrap();
+    std::fs::write(dir.path().join("target/release/crom"), "crom").unwrap();
+
+    let mut paths = HashMap::new();
+    paths.insert(s!("crom"), s!("target/release/crom"));
+
+    let mut artifacts = HashMap::new();
+    artifacts.insert(
+        s!("linux"),
+        ProjectArtifacts {
+            paths: paths.clone(),
+            compress: None,
+            target: ProjectArtifactTarget::GitHub,
+        },
+    );
+
+    paths.insert(s!("crom.sig"), s!("target/release/crom.sig"));
+    artifacts.insert(
+        s!("mac"),
+        ProjectArtifacts {
+            paths,
+            compress: None,
+            target: ProjectArtifactTarget::GitHub,
+        },
+    );
+
+    let resolved = find_artifacts(dir.path(), &artifacts, &[s!("linux")]).unwrap();
+    assert_eq!(
+        vec![ResolvedArtifact {
+            name: s!("linux"),
+            target: ProjectArtifactTarget::GitHub,
+            compress: None,
+            files: vec![(s!("crom"), dir.path().join("target/release/crom"))],
+        }],
+        resolved
+    );
+
+    let err = find_artifacts(dir.path(), &artifacts, &[s!("linux"), s!("mac")]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::ArtifactMissing(_)));
+
+    let err = find_artifacts(dir.path(), &artifacts, &[s!("windows")]).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::ArtifactMissing(_)));
+}
+
+#[test]
+fn test_package_artifact() {
+    use crate::models::ProjectArtifactCompressionFormat;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("crom"), "crom").unwrap();
+
+    let mut artifact = ResolvedArtifact {
+        name: s!("linux"),
+        target: ProjectArtifactTarget::GitHub,
+        compress: None,
+        files: vec![(s!("crom"), dir.path().join("crom"))],
+    };
+    let output_dir = dir.path().join("out");
+
+    assert_eq!(artifact.files, artifact.package(&output_dir).unwrap());
+
+    artifact.compress = Some(ProjectArtifactWrapper {
+        name: s!("linux.tar.gz"),
+        format: ProjectArtifactCompressionFormat::Tgz,
+    });
+
+    assert_eq!(
+        vec![(s!("linux.tar.gz"), output_dir.join("linux.tar.gz"))],
+        artifact.package(&output_dir).unwrap()
+    );
+    assert!(output_dir.join("linux.tar.gz").exists());
+}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use log::info;
+
+use crate::cli::{ChangedArgs, VersionRequest};
+use crate::CromResult;
+
+pub struct ChangedCommand;
+
+#[async_trait]
+impl super::CommandRunner<ChangedArgs> for ChangedCommand {
+    async fn run_command(args: ChangedArgs) -> CromResult<i32> {
+        let versions = super::create_versions(
+            &VersionRequest::Latest,
+            args.component_opts.component.as_deref(),
+            &args.history_opts,
+            None,
+            crate::version::today(),
+        )
+        .await?;
+
+        let show_names = versions.len() > 1;
+        let mut any_changed = false;
+        for (_, location, component, details) in versions {
+            let changed = super::has_changed(&location, &component)?;
+            any_changed |= changed;
+
+            let status = if changed { "changed" } else { "unchanged" };
+            match &details.latest {
+                Some((latest, _)) => info!("Files {} since {}", status, latest),
+                None => info!("No tags match {}, so everything changed", details.pattern),
+            }
+            match component.name {
+                Some(name) if show_names => println!("{} {}", name, status),
+                _ => println!("{}", status),
+            }
+        }
+
+        Ok(if any_changed { 0 } else { 1 })
+    }
+}
@@ -0,0 +1,267 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use error_chain::bail;
+use log::info;
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use super::VersionDetails;
+use crate::cli::{EnvArgs, EnvFormat, VersionRequest};
+use crate::errors::ErrorKind;
+use crate::version::{Version, VersionBump, VersionMatcher};
+use crate::CromResult;
+
+pub struct EnvCommand;
+
+#[async_trait]
+impl super::CommandRunner<EnvArgs> for EnvCommand {
+    async fn run_command(args: EnvArgs) -> CromResult<i32> {
+        let bump = args.bump.bump;
+        let today = crate::version::today();
+        let versions = super::create_versions(
+            &VersionRequest::Current { bump, dirty: false },
+            args.component_opts.component.as_deref(),
+            &args.history_opts,
+            None,
+            today,
+        )
+        .await?;
+
+        let show_names = versions.len() > 1;
+        let mut variables = Vec::new();
+        for (version, _, component, details) in versions {
+            let prefix = match &component.name {
+                Some(name) if show_names => format!("CROM_{}_", env_name(name)),
+                _ => s!("CROM_"),
+            };
+
+            variables.extend(make_variables(&prefix, &version, &details, bump, today)?);
+        }
+
+        let lookup = |name: &str| env::var(name).ok();
+        let format = match args.format {
+            Some(format) => format,
+            None => detect_format(lookup),
+        };
+        let output = match args.output {
+            Some(output) => Some(PathBuf::from(output)),
+            None => default_output(&format, lookup)?,
+        };
+
+        let text = format_variables(&format, &variables);
+        match output {
+            Some(path) => {
+                write_output(&format, &path, &text)?;
+                info!("Wrote {:?} variables to {:?}", format, path);
+            }
+            None => print!("{}", text),
+        }
+
+        Ok(0)
+    }
+}
+
+/// The variables for one component.
+///
+/// Latest and next are worked out from the tags found for the current
+/// version, so every variable comes from the same state of the repo. The
+/// latest version is empty when nothing has been tagged yet.
+fn make_variables(
+    prefix: &str,
+    version: &Version,
+    details: &VersionDetails,
+    bump: VersionBump,
+    today: NaiveDate,
+) -> CromResult<Vec<(String, String)>> {
+    let latest = details.latest.as_ref().map(|(latest, _)| latest.clone());
+    let base = match &latest {
+        Some(latest) => latest.clone(),
+        None => VersionMatcher::new(&details.pattern).build_default_version(),
+    };
+    super::check_bump(&base, bump)?;
+    let next = base.next_version_on(bump, None, today);
+
+    Ok(vec![
+        (format!("{}VERSION", prefix), version.to_string()),
+        (format!("{}NEXT_VERSION", prefix), next.to_string()),
+        (
+            format!("{}LATEST_VERSION", prefix),
+            latest.map(|latest| latest.to_string()).unwrap_or_default(),
+        ),
+        (format!("{}SHA", prefix), details.head.clone()),
+        (
+            format!("{}IS_RELEASE", prefix),
+            details.is_release.to_string(),
+        ),
+    ])
+}
+
+/// Figure out which CI system crom is running in.
+fn detect_format<F>(lookup: F) -> EnvFormat
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if lookup("GITHUB_ACTIONS").as_deref() == Some("true") {
+        EnvFormat::Github
+    } else if lookup("GITLAB_CI").as_deref() == Some("true") {
+        EnvFormat::Gitlab
+    } else {
+        EnvFormat::Shell
+    }
+}
+
+fn default_output<F>(format: &EnvFormat, lookup: F) -> CromResult<Option<PathBuf>>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    match format {
+        EnvFormat::Github => match lookup("GITHUB_OUTPUT") {
+            Some(path) => Ok(Some(PathBuf::from(path))),
+            None => bail!(ErrorKind::UserError(s!(
+                "GITHUB_OUTPUT is not set, use --output"
+            ))),
+        },
+        EnvFormat::Gitlab => Ok(Some(PathBuf::from(crate::statics::GITLAB_DOTENV_FILE))),
+        EnvFormat::Shell => Ok(None),
+    }
+}
+
+fn format_variables(format: &EnvFormat, variables: &[(String, String)]) -> String {
+    variables
+        .iter()
+        .map(|(name, value)| match format {
+            EnvFormat::Github | EnvFormat::Gitlab => format!("{}={}\n", name, value),
+            EnvFormat::Shell => format!("export {}='{}'\n", name, value.replace('\'', "'\\''")),
+        })
+        .collect()
+}
+
+/// `$GITHUB_OUTPUT` is shared by every step so it's appended to, other
+/// files are replaced.
+fn write_output(format: &EnvFormat, path: &std::path::Path, text: &str) -> CromResult<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(*format == EnvFormat::Github)
+        .truncate(*format != EnvFormat::Github)
+        .open(path)?;
+    file.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+/// Turn a component name into something usable in a variable name.
+fn env_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_detect_format() {
+    let github = |name: &str| match name {
+        "GITHUB_ACTIONS" => Some(s!("true")),
+        "GITHUB_OUTPUT" => Some(s!("/tmp/github_output")),
+        _ => None,
+    };
+    let gitlab = |name: &str| match name {
+        "GITLAB_CI" => Some(s!("true")),
+        _ => None,
+    };
+    let none = |_: &str| None;
+
+    assert_eq!(EnvFormat::Github, detect_format(github));
+    assert_eq!(EnvFormat::Gitlab, detect_format(gitlab));
+    assert_eq!(EnvFormat::Shell, detect_format(none));
+
+    assert_eq!(
+        Some(PathBuf::from("/tmp/github_output")),
+        default_output(&EnvFormat::Github, github).unwrap()
+    );
+    assert!(default_output(&EnvFormat::Github, none).is_err());
+    assert_eq!(
+        Some(PathBuf::from("crom.env")),
+        default_output(&EnvFormat::Gitlab, none).unwrap()
+    );
+    assert_eq!(None, default_output(&EnvFormat::Shell, none).unwrap());
+}
+
+#[test]
+fn test_write_output() {
+    let variables = vec![
+        (s!("CROM_VERSION"), s!("v0.1.5")),
+        (s!("CROM_IS_RELEASE"), s!("true")),
+    ];
+    let dir = tempfile::tempdir().unwrap();
+
+    let github = dir.path().join("github_output");
+    std::fs::write(&github, "other=value\n").unwrap();
+    let text = format_variables(&EnvFormat::Github, &variables);
+    write_output(&EnvFormat::Github, &github, &text).unwrap();
+    assert_eq!(
+        "other=value\nCROM_VERSION=v0.1.5\nCROM_IS_RELEASE=true\n",
+        std::fs::read_to_string(&github).unwrap()
+    );
+
+    let gitlab = dir.path().join("crom.env");
+    std::fs::write(&gitlab, "OLD=value\n").unwrap();
+    let text = format_variables(&EnvFormat::Gitlab, &variables);
+    write_output(&EnvFormat::Gitlab, &gitlab, &text).unwrap();
+    assert_eq!(
+        "CROM_VERSION=v0.1.5\nCROM_IS_RELEASE=true\n",
+        std::fs::read_to_string(&gitlab).unwrap()
+    );
+
+    assert_eq!(
+        "export CROM_VERSION='it'\\''s'\n",
+        format_variables(&EnvFormat::Shell, &[(s!("CROM_VERSION"), s!("it's"))])
+    );
+    assert_eq!("SERVER_UI", env_name("server-ui"));
+}
+
+#[test]
+fn test_make_variables() {
+    let today = NaiveDate::from_ymd_opt(2026, 10, 18).unwrap();
+    let matcher = VersionMatcher::new("v1.%d");
+    let latest = matcher.match_version(s!("v1.3")).unwrap();
+    let mut details = VersionDetails {
+        config_path: PathBuf::from("/repo/.crom.toml"),
+        pattern: s!("v1.%d"),
+        head: s!("3a0ac8b7"),
+        latest: Some((latest, s!("dc03619a"))),
+        distance: Some(1),
+        dirty: false,
+        is_release: false,
+    };
+    let version = Version::from(s!("v1.4-3a0ac8b"));
+
+    assert_eq!(
+        vec![
+            (s!("CROM_VERSION"), s!("v1.4-3a0ac8b")),
+            (s!("CROM_NEXT_VERSION"), s!("v1.4")),
+            (s!("CROM_LATEST_VERSION"), s!("v1.3")),
+            (s!("CROM_SHA"), s!("3a0ac8b7")),
+            (s!("CROM_IS_RELEASE"), s!("false")),
+        ],
+        make_variables("CROM_", &version, &details, VersionBump::Patch, today).unwrap()
+    );
+
+    details.latest = None;
+    let variables =
+        make_variables("CROM_UI_", &version, &details, VersionBump::Patch, today).unwrap();
+    assert_eq!((s!("CROM_UI_NEXT_VERSION"), s!("v1.1")), variables[1]);
+    assert_eq!((s!("CROM_UI_LATEST_VERSION"), s!("")), variables[2]);
+
+    assert!(make_variables("CROM_", &version, &details, VersionBump::Major, today).is_err());
+
+    details.pattern = s!("%Y.%m.%n");
+    let variables = make_variables("CROM_", &version, &details, VersionBump::Patch, today).unwrap();
+    assert_eq!((s!("CROM_NEXT_VERSION"), s!("2026.10.0")), variables[1]);
+}
@@ -1,6 +1,11 @@
 use async_trait::async_trait;
+use log::info;
+use serde_json::{json, Value};
 
-use crate::cli::GetArgs;
+use super::VersionDetails;
+use crate::cli::{GetArgs, OutputFormat, VersionRequest};
+use crate::models::Component;
+use crate::version::Version;
 use crate::CromResult;
 
 pub struct GetCommand;
@@ -8,10 +13,199 @@ pub struct GetCommand;
 #[async_trait]
 impl super::CommandRunner<GetArgs> for GetCommand {
     async fn run_command(args: GetArgs) -> CromResult<i32> {
-        let (version, _, _) =
-            super::create_version(args.sub_command.make_version_request()).await?;
+        let request = args.sub_command.make_version_request();
+        let versions = super::create_versions(
+            &request,
+            args.component_opts.component.as_deref(),
+            &args.history_opts,
+            args.remote.as_deref(),
+            crate::version::today(),
+        )
+        .await?;
 
-        println!("{}", version);
-        Ok(0)
+        let latest = matches!(request, VersionRequest::Latest);
+        let if_changed = args.sub_command.if_changed();
+        let show_names = versions.len() > 1;
+        let mut shown = Vec::new();
+        for (version, location, component, details) in versions {
+            if latest && details.latest.is_none() {
+                info!(
+                    "No tags match {}, so there is no latest version",
+                    details.pattern
+                );
+                continue;
+            }
+
+            if if_changed && !super::has_changed(&location, &component)? {
+                info!("Nothing changed, skipping {}", version);
+                continue;
+            }
+
+            shown.push((version, component, details));
+        }
+
+        let (output, code) = format_versions(&args.format, show_names, &shown);
+        if let Some(output) = output {
+            println!("{}", output);
+        }
+
+        Ok(code)
     }
 }
+
+/// Text to print for the versions that were found, and the exit code, which
+/// is 1 when there is no version to show.
+///
+/// With more than one component configured the JSON is always an array, even
+/// when `--if-changed` leaves one or none of them.
+fn format_versions(
+    format: &OutputFormat,
+    show_names: bool,
+    versions: &[(Version, Component, VersionDetails)],
+) -> (Option<String>, i32) {
+    let code = if versions.is_empty() { 1 } else { 0 };
+
+    let output = match format {
+        OutputFormat::Text if versions.is_empty() => None,
+        OutputFormat::Text => Some(
+            versions
+                .iter()
+                .map(|(version, component, _)| match &component.name {
+                    Some(name) if show_names => format!("{} {}", name, version),
+                    _ => version.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        OutputFormat::Json => {
+            let mut output: Vec<Value> = versions
+                .iter()
+                .map(|(version, component, details)| make_json(version, component, details))
+                .collect();
+            let json = if show_names {
+                Value::Array(output)
+            } else {
+                output.pop().unwrap_or(Value::Null)
+            };
+            serde_json::to_string_pretty(&json).ok()
+        }
+    };
+
+    (output, code)
+}
+
+fn make_json(version: &Version, component: &Component, details: &VersionDetails) -> Value {
+    let latest = details.latest.as_ref().map(|(version, commit)| {
+        json!({
+            "version": version.to_string(),
+            "commit": commit,
+        })
+    });
+
+    json!({
+        "component": component.name,
+        "version": version.to_string(),
+        "stripped": version.stripped(),
+        "parts": version.numbers(),
+        "pre_release": version.pre_release(),
+        "build": version.build(),
+        "pattern": details.pattern,
+        "head": details.head,
+        "latest": latest,
+        "distance": details.distance,
+        "dirty": details.dirty,
+        "config": details.config_path.to_string_lossy(),
+    })
+}
+
+#[test]
+fn test_make_json() {
+    use crate::models::CromConfig;
+    use crate::version::VersionMatcher;
+    use std::path::PathBuf;
+
+    let config = CromConfig::create_default(s!("v1.%d.%d"), s!("Release {version}!"));
+    let component = &config.components(None).unwrap()[0];
+    let matcher = VersionMatcher::new("v1.%d.%d");
+    let latest = matcher.match_version(s!("v1.2.3")).unwrap();
+    let version = latest.with_metadata(Some(s!("0cc81e3")), None);
+    let details = VersionDetails {
+        config_path: PathBuf::from("/repo/.crom.toml"),
+        pattern: s!("v1.%d.%d"),
+        head: s!("0cc81e3b5d1f"),
+        latest: Some((latest, s!("dc03619a"))),
+        distance: Some(2),
+        dirty: true,
+        is_release: false,
+    };
+
+    assert_eq!(
+        json!({
+            "component": null,
+            "version": "v1.2.3-0cc81e3",
+            "stripped": "1.2.3-0cc81e3",
+            "parts": [1, 2, 3],
+            "pre_release": "0cc81e3",
+            "build": null,
+            "pattern": "v1.%d.%d",
+            "head": "0cc81e3b5d1f",
+            "latest": {
+                "version": "v1.2.3",
+                "commit": "dc03619a",
+            },
+            "distance": 2,
+            "dirty": true,
+            "config": "/repo/.crom.toml",
+        }),
+        make_json(&version, component, &details)
+    );
+}
+
+#[test]
+fn test_format_versions() {
+    use crate::models::CromConfig;
+    use crate::version::VersionMatcher;
+    use std::path::PathBuf;
+
+    let config = CromConfig::create_default(s!("v1.%d"), s!("Release {version}!"));
+    let mut component = config.components(None).unwrap().remove(0);
+    component.name = Some(s!("server"));
+    let version = VersionMatcher::new("v1.%d")
+        .match_version(s!("v1.2"))
+        .unwrap();
+    let details = VersionDetails {
+        config_path: PathBuf::from("/repo/.crom.toml"),
+        pattern: s!("v1.%d"),
+        head: s!("0cc81e3b5d1f"),
+        latest: None,
+        distance: Some(0),
+        dirty: false,
+        is_release: true,
+    };
+    let versions = vec![(version, component, details)];
+
+    assert_eq!(
+        (Some(s!("v1.2")), 0),
+        format_versions(&OutputFormat::Text, false, &versions)
+    );
+    assert_eq!(
+        (Some(s!("server v1.2")), 0),
+        format_versions(&OutputFormat::Text, true, &versions)
+    );
+    assert_eq!((None, 1), format_versions(&OutputFormat::Text, false, &[]));
+
+    let (json, code) = format_versions(&OutputFormat::Json, true, &versions);
+    assert_eq!(0, code);
+    assert!(serde_json::from_str::<Value>(&json.unwrap())
+        .unwrap()
+        .is_array());
+
+    assert_eq!(
+        (Some(s!("[]")), 1),
+        format_versions(&OutputFormat::Json, true, &[])
+    );
+    assert_eq!(
+        (Some(s!("null")), 1),
+        format_versions(&OutputFormat::Json, false, &[])
+    );
+}
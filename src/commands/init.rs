@@ -42,7 +42,7 @@ fn write_default_config<P: AsRef<Path>>(default_format: &str, dest: P) -> CromRe
 
     let crom_config = CromConfig::create_default(
         default_format.to_string(),
-        "Created {version} for release -- Crom".to_string(),
+        s!(crate::statics::DEFAULT_MESSAGE_TEMPLATE),
     );
 
     let text = toml::to_string_pretty(&crom_config)?;
@@ -1,18 +1,26 @@
 use crate::git_repo;
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use error_chain::bail;
+use git2::{Oid, Repository};
 use log::{debug, error};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+mod artifact;
+mod changed;
+mod env;
 mod get;
 mod init;
+mod release;
+mod tag;
+mod upload;
 mod utils;
 mod write;
 
-use crate::cli::VersionRequest;
+use crate::cli::{HistoryOpts, VersionRequest};
 use crate::errors::ErrorKind;
-use crate::models::CromConfig;
-use crate::version::Version;
+use crate::models::{Component, CromConfig, ProjectConfig};
+use crate::version::{PreReleaseTemplate, PreReleaseValues, Version, VersionBump, VersionMatcher};
 use crate::CromResult;
 
 #[async_trait]
@@ -23,14 +31,38 @@ where
     async fn run_command(arg: T) -> CromResult<i32>;
 }
 
+pub async fn run_artifact(args: crate::cli::ArtifactArgs) -> CromResult<i32> {
+    artifact::ArtifactCommand::run_command(args).await
+}
+
 pub async fn run_init(args: crate::cli::InitArgs) -> CromResult<i32> {
     init::InitCommand::run_command(args).await
 }
 
+pub async fn run_changed(args: crate::cli::ChangedArgs) -> CromResult<i32> {
+    changed::ChangedCommand::run_command(args).await
+}
+
+pub async fn run_env(args: crate::cli::EnvArgs) -> CromResult<i32> {
+    env::EnvCommand::run_command(args).await
+}
+
 pub async fn run_get(args: crate::cli::GetArgs) -> CromResult<i32> {
     get::GetCommand::run_command(args).await
 }
 
+pub async fn run_release(args: crate::cli::ReleaseArgs) -> CromResult<i32> {
+    release::ReleaseCommand::run_command(args).await
+}
+
+pub async fn run_tag(args: crate::cli::TagArgs) -> CromResult<i32> {
+    tag::TagCommand::run_command(args).await
+}
+
+pub async fn run_upload(args: crate::cli::UploadArgs) -> CromResult<i32> {
+    upload::UploadCommand::run_command(args).await
+}
+
 pub async fn run_utils(args: crate::cli::UtilityArgs) -> CromResult<i32> {
     utils::UtilsCommand::run_command(args).await
 }
@@ -55,95 +87,710 @@ pub fn are_you_sure(default: bool) -> CromResult<bool> {
     }
 }
 
-async fn create_version(request: VersionRequest) -> CromResult<(Version, PathBuf, CromConfig)> {
-    use git2::Repository;
+/// What was found in the repo while building a version.
+#[derive(Debug, Clone)]
+pub struct VersionDetails {
+    /// Path of the `.crom.toml` file
+    pub config_path: PathBuf,
+    /// Pattern used for the component, after branch rules are applied
+    pub pattern: String,
+    /// Full sha of HEAD
+    pub head: String,
+    /// Latest matching tag and the full sha of its commit
+    pub latest: Option<(Version, String)>,
+    /// Number of commits since the latest tag HEAD contains, unknown in a
+    /// shallow clone
+    pub distance: Option<usize>,
+    /// True when there are uncommitted changes
+    pub dirty: bool,
+    /// True when HEAD is tagged with a version
+    pub is_release: bool,
+}
 
+/// Build the requested version for each component.
+///
+/// Returns the version, the directory of the component, the component
+/// and details about how the version was found. The tags are read from
+/// `remote` when it's set, and `today` is used for the date parts of
+/// calendar versions.
+async fn create_versions(
+    request: &VersionRequest,
+    component: Option<&str>,
+    history: &HistoryOpts,
+    remote: Option<&str>,
+    today: NaiveDate,
+) -> CromResult<Vec<(Version, PathBuf, Component, VersionDetails)>> {
     let (location, config) = crate::models::find_project_config().await?;
     debug!("Parsed config: {:?}", config);
 
-    let repo = Repository::discover(location.clone())?;
-    let matcher = config.create_version_matcher();
-    let mut versions: Vec<Version> = git_repo::get_tags(&repo, &matcher)?;
-    versions.sort();
+    build_versions(
+        &location, &config, request, component, history, remote, today,
+    )
+}
+
+/// `create_versions` for the config at `location`.
+fn build_versions(
+    location: &Path,
+    config: &CromConfig,
+    request: &VersionRequest,
+    component: Option<&str>,
+    history: &HistoryOpts,
+    remote: Option<&str>,
+    today: NaiveDate,
+) -> CromResult<Vec<(Version, PathBuf, Component, VersionDetails)>> {
+    let repo = Repository::discover(location)?;
+    check_history(&repo, history, request, remote.is_some())?;
+    let branch = current_branch(&repo)?;
+    let dirty = !git_repo::is_working_repo_clean(&repo)?;
+
+    let mut results = Vec::new();
+    for component in config.components(component)? {
+        let project = &component.project;
+        let (matcher, versions) = find_versions(&repo, project, &branch, remote)?;
+
+        let default_version = matcher.build_default_version();
+        let latest_version = match versions.last() {
+            Some((version, _)) => version,
+            None => &default_version,
+        };
+
+        // Like `git describe`, describe and the distance only use tags that
+        // HEAD contains, whatever `tag-scope` is. `check_history` only allows
+        // a shallow clone when neither is used.
+        let (reachable, distance) = if repo.is_shallow() {
+            (Vec::new(), None)
+        } else {
+            let reachable = git_repo::reachable_versions(&repo, &versions)?;
+            let since = reachable.last().map(|(_, commit)| *commit);
+            let distance = git_repo::get_distance(&repo, since)?;
+            (reachable, Some(distance))
+        };
+        let base_version = match (request, reachable.last()) {
+            (VersionRequest::Describe, Some((version, _))) => version,
+            (VersionRequest::Describe, None) => &default_version,
+            _ => latest_version,
+        };
+
+        let values = PreReleaseValues {
+            sha: git_repo::get_head_sha(location.to_path_buf(), &repo)?,
+            branch: branch.clone(),
+            timestamp: git_repo::get_head_time(&repo)?,
+            distance: distance.unwrap_or_default(),
+        };
+        let template = project.create_pre_release_template()?;
+
+        let head_version = git_repo::get_head_version(&repo, &versions)?;
+
+        let mut version = build_version(
+            request,
+            &template,
+            &values,
+            base_version,
+            head_version.as_ref(),
+            today,
+        )?;
+        if dirty && matches!(request, VersionRequest::Current { dirty: true, .. }) {
+            version = version.with_dirty_marker();
+        }
+
+        let latest = versions
+            .last()
+            .map(|(version, commit)| (version.clone(), commit.to_string()));
+
+        let details = VersionDetails {
+            config_path: location.join(crate::statics::CONFIG_FILE),
+            pattern: component.project.find_pattern(&branch),
+            head: values.sha.clone(),
+            latest,
+            distance,
+            dirty,
+            is_release: head_version.is_some(),
+        };
+
+        results.push((version, component.root(location), component, details));
+    }
+
+    Ok(results)
+}
+
+/// Make sure the repo has the history needed to compute versions, fetching
+/// the full history and tags first when asked to.
+///
+/// Custom versions don't use the history, and neither do `latest` and
+/// `next-release` when `remote` is set, since the tags are read from the
+/// remote. For those a shallow clone isn't an error. The local tags are
+/// only checked when they are used.
+fn check_history(
+    repo: &Repository,
+    history: &HistoryOpts,
+    request: &VersionRequest,
+    remote: bool,
+) -> CromResult<()> {
+    let needs_history = match request {
+        VersionRequest::Custom(_) => false,
+        VersionRequest::Latest | VersionRequest::NextRelease(_) => !remote,
+        _ => true,
+    };
+
+    if history.fetch {
+        if repo.is_shallow() {
+            git_repo::unshallow(repo, &history.fetch_remote)?;
+        }
+        git_repo::fetch_tags(repo, &history.fetch_remote)?;
+    }
+
+    if needs_history && repo.is_shallow() {
+        bail!(ErrorKind::ShallowRepo(repo.path().to_path_buf()));
+    }
 
+    if needs_history
+        && !remote
+        && !history.allow_no_tags
+        && repo.tag_names(None)?.is_empty()
+        && !repo.remotes()?.is_empty()
+    {
+        bail!(ErrorKind::NoTagsFound(repo.path().to_path_buf()));
+    }
+
+    Ok(())
+}
+
+/// Fail when `latest` was asked for but a component has no tags yet, so a
+/// command doesn't act on a version that was never released.
+fn check_latest(
+    request: &VersionRequest,
+    versions: &[(Version, PathBuf, Component, VersionDetails)],
+) -> CromResult<()> {
+    if !matches!(request, VersionRequest::Latest) {
+        return Ok(());
+    }
+
+    for (_, _, _, details) in versions {
+        if details.latest.is_none() {
+            bail!(ErrorKind::NoLatestVersion(details.pattern.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+fn current_branch(repo: &Repository) -> CromResult<String> {
+    let branch = match std::env::var(crate::statics::BRANCH_ENV_VAR) {
+        Ok(branch) if !branch.is_empty() => branch,
+        _ => git_repo::get_branch_name(repo)?,
+    };
+
+    debug!("Using branch {}", branch);
+    Ok(branch)
+}
+
+/// Tags matching the project's pattern and the commits they point at,
+/// sorted from oldest to newest.
+///
+/// When `remote` is given, the tags are listed from the remote and
+/// `tag-scope` isn't used since the commits may not be in the local repo.
+fn find_versions(
+    repo: &Repository,
+    project: &ProjectConfig,
+    branch: &str,
+    remote: Option<&str>,
+) -> CromResult<(VersionMatcher, Vec<(Version, Oid)>)> {
+    let matcher = project.create_version_matcher(branch);
+    if let Some(remote) = remote {
+        let versions = git_repo::get_remote_tags(repo, remote, &matcher)?;
+        return Ok((matcher, versions));
+    }
+
+    let mut versions = git_repo::get_tags(repo, &matcher, &project.tag_scope())?;
+    versions.sort();
     debug!("Found the following tags: {:?}", &versions);
 
-    let default_version = matcher.build_default_version();
-    let latest_version = versions.last().unwrap_or(&default_version);
+    let mut commits = Vec::new();
+    for version in versions {
+        let commit = git_repo::find_tag_commit(repo, &version)?.id();
+        commits.push((version, commit));
+    }
 
-    let mut head = git_repo::get_head_sha(location.clone(), &repo)?;
-    head.truncate(7);
+    Ok((matcher, commits))
+}
+
+/// Whether the component's `paths` changed since its latest version.
+///
+/// `location` is the component's directory, as returned by `create_versions`.
+fn has_changed(location: &Path, component: &Component) -> CromResult<bool> {
+    let repo = Repository::discover(location)?;
+    let workdir = match repo.workdir() {
+        Some(workdir) => workdir.canonicalize()?,
+        None => bail!(ErrorKind::UnknownGitHead(location.to_path_buf())),
+    };
+
+    let prefix = match location.canonicalize()?.strip_prefix(&workdir) {
+        Ok(prefix) => prefix.to_string_lossy().replace('\\', "/"),
+        Err(_) => s!(""),
+    };
 
-    let version = build_version(request, head, latest_version);
+    let branch = current_branch(&repo)?;
+    let (_, versions) = find_versions(&repo, &component.project, &branch, None)?;
+    let patterns = component.create_path_patterns(&prefix)?;
+
+    git_repo::has_changes(
+        &repo,
+        versions.last().map(|(version, _)| version),
+        &patterns,
+    )
+}
+
+/// `head_version` is the newest version tagged on HEAD, if any.
+fn build_version(
+    request: &VersionRequest,
+    template: &PreReleaseTemplate,
+    values: &PreReleaseValues,
+    latest_version: &Version,
+    head_version: Option<&Version>,
+    today: NaiveDate,
+) -> CromResult<Version> {
+    let version = match (request, head_version) {
+        (VersionRequest::Custom(version), _) => Version::from(version.clone()),
+        (VersionRequest::Current { .. }, Some(head_version)) => head_version.clone(),
+        (VersionRequest::PreRelease(bump), _) | (VersionRequest::Current { bump, .. }, None) => {
+            check_bump(latest_version, *bump)?;
+            template.render(&latest_version.next_version_on(*bump, None, today), values)
+        }
+        (VersionRequest::NextRelease(bump), _) => {
+            check_bump(latest_version, *bump)?;
+            latest_version.next_version_on(*bump, None, today)
+        }
+        (VersionRequest::Latest, _) => latest_version.clone(),
+        (VersionRequest::Describe, _) => {
+            if values.distance == 0 {
+                latest_version.clone()
+            } else {
+                let sha: String = values.sha.chars().take(7).collect();
+                latest_version.with_metadata(Some(format!("{}-g{}", values.distance, sha)), None)
+            }
+        }
+    };
 
-    Ok((version, location, config))
+    Ok(version)
 }
 
-fn build_version(request: VersionRequest, head: String, latest_version: &Version) -> Version {
-    match &request {
-        VersionRequest::Custom(version) => Version::from(version.clone()),
-        VersionRequest::PreRelease => latest_version.next_version(Some(head)),
-        VersionRequest::NextRelease => latest_version.next_version(None),
-        VersionRequest::Latest => latest_version.clone(),
+fn check_bump(version: &Version, bump: VersionBump) -> CromResult<()> {
+    if !version.can_bump(bump) {
+        bail!(ErrorKind::InvalidBump(format!(
+            "The pattern for {} has no counter for {:?}",
+            version, bump
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+fn test_values() -> PreReleaseValues {
+    PreReleaseValues {
+        sha: s!("abc123"),
+        branch: s!("main"),
+        timestamp: 0,
+        distance: 3,
     }
 }
 
 #[test]
 fn test_latest_release() {
-    use crate::version::VersionMatcher;
-
     let matcher = VersionMatcher::new("1.2.%d");
     let latest_version = matcher.match_version(s!("1.2.3")).unwrap();
 
     let version = build_version(
-        VersionRequest::Latest,
-        "abc123".to_string(),
+        &VersionRequest::Latest,
+        &PreReleaseTemplate::default(),
+        &test_values(),
         &latest_version,
-    );
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
     assert_eq!(s!("1.2.3"), version.to_string());
 }
 
 #[test]
 fn test_next_release() {
-    use crate::version::VersionMatcher;
-
     let matcher = VersionMatcher::new("1.2.%d");
     let latest_version = matcher.match_version(s!("1.2.3")).unwrap();
 
     let version = build_version(
-        VersionRequest::NextRelease,
-        "abc123".to_string(),
+        &VersionRequest::NextRelease(VersionBump::Patch),
+        &PreReleaseTemplate::default(),
+        &test_values(),
         &latest_version,
-    );
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
     assert_eq!(s!("1.2.4"), version.to_string());
 }
 
 #[test]
 fn test_custom_version() {
-    use crate::version::VersionMatcher;
-
     let matcher = VersionMatcher::new("1.2.%d");
     let latest_version = matcher.match_version(s!("1.2.3")).unwrap();
 
     let version = build_version(
-        VersionRequest::Custom(s!("4.5.3")),
-        "abc123".to_string(),
+        &VersionRequest::Custom(s!("4.5.3")),
+        &PreReleaseTemplate::default(),
+        &test_values(),
         &latest_version,
-    );
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
     assert_eq!(s!("4.5.3"), version.to_string());
 }
 
 #[test]
 fn test_pre_release() {
-    use crate::version::VersionMatcher;
-
     let matcher = VersionMatcher::new("1.2.%d");
     let latest_version = matcher.match_version(s!("1.2.3")).unwrap();
 
     let version = build_version(
-        VersionRequest::PreRelease,
-        "abc123".to_string(),
+        &VersionRequest::PreRelease(VersionBump::Patch),
+        &PreReleaseTemplate::default(),
+        &test_values(),
         &latest_version,
-    );
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
     assert_eq!(s!("1.2.4-abc123"), version.to_string());
 }
+
+#[test]
+fn test_next_release_bump() {
+    let matcher = VersionMatcher::new("1.%d.%d");
+    let latest_version = matcher.match_version(s!("1.2.3")).unwrap();
+
+    let version = build_version(
+        &VersionRequest::NextRelease(VersionBump::Minor),
+        &PreReleaseTemplate::default(),
+        &test_values(),
+        &latest_version,
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
+    assert_eq!(s!("1.3.0"), version.to_string());
+
+    let err = build_version(
+        &VersionRequest::NextRelease(VersionBump::Major),
+        &PreReleaseTemplate::default(),
+        &test_values(),
+        &latest_version,
+        None,
+        crate::version::today(),
+    )
+    .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidBump(_)));
+}
+
+#[test]
+fn test_pre_release_template() {
+    let matcher = VersionMatcher::new("1.2.%d");
+    let latest_version = matcher.match_version(s!("1.2.3")).unwrap();
+
+    let version = build_version(
+        &VersionRequest::PreRelease(VersionBump::Patch),
+        &PreReleaseTemplate::parse("{next}-SNAPSHOT").unwrap(),
+        &test_values(),
+        &latest_version,
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
+    assert_eq!(s!("1.2.4-SNAPSHOT"), version.to_string());
+}
+
+#[test]
+fn test_describe() {
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let latest_version = matcher.match_version(s!("v0.1.4")).unwrap();
+    let template = PreReleaseTemplate::default();
+    let mut values = test_values();
+
+    let version = build_version(
+        &VersionRequest::Describe,
+        &template,
+        &values,
+        &latest_version,
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
+    assert_eq!(s!("v0.1.4-3-gabc123"), version.to_string());
+
+    values.distance = 0;
+    let version = build_version(
+        &VersionRequest::Describe,
+        &template,
+        &values,
+        &latest_version,
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
+    assert_eq!(s!("v0.1.4"), version.to_string());
+}
+
+#[test]
+fn test_current() {
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let latest_version = matcher.match_version(s!("v0.1.4")).unwrap();
+    let request = VersionRequest::Current {
+        bump: VersionBump::Patch,
+        dirty: false,
+    };
+
+    let version = build_version(
+        &request,
+        &PreReleaseTemplate::default(),
+        &test_values(),
+        &latest_version,
+        Some(&latest_version),
+        crate::version::today(),
+    )
+    .unwrap();
+    assert_eq!(s!("v0.1.4"), version.to_string());
+
+    let version = build_version(
+        &request,
+        &PreReleaseTemplate::default(),
+        &test_values(),
+        &latest_version,
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
+    assert_eq!(s!("v0.1.5-abc123"), version.to_string());
+}
+
+#[test]
+fn test_check_history() {
+    let history = HistoryOpts {
+        fetch: false,
+        fetch_remote: s!("origin"),
+        allow_no_tags: false,
+    };
+
+    let (_remote_dir, remote) = create_bare_remote(2);
+
+    let dir = tempfile::tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    check_history(&repo, &history, &VersionRequest::Latest, false).unwrap();
+
+    let url = format!("file://{}", remote.path().display());
+    repo.remote("origin", &url).unwrap();
+    let err = check_history(&repo, &history, &VersionRequest::Latest, false).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::NoTagsFound(_)));
+    check_history(&repo, &history, &VersionRequest::Custom(s!("1.2.3")), false).unwrap();
+
+    let allow = HistoryOpts {
+        allow_no_tags: true,
+        ..history.clone()
+    };
+    check_history(&repo, &allow, &VersionRequest::Latest, false).unwrap();
+
+    let fetch = HistoryOpts {
+        fetch: true,
+        ..history.clone()
+    };
+    check_history(&repo, &fetch, &VersionRequest::Latest, false).unwrap();
+    assert!(repo.find_reference("refs/tags/v0.1.1").is_ok());
+}
+
+#[test]
+fn test_check_history_shallow() {
+    let history = HistoryOpts {
+        fetch: false,
+        fetch_remote: s!("origin"),
+        allow_no_tags: false,
+    };
+
+    let (_remote_dir, remote) = create_bare_remote(3);
+    let dir = tempfile::tempdir().unwrap();
+    let status = std::process::Command::new("git")
+        .args(["clone", "-q", "--depth", "1", "--no-tags"])
+        .arg(format!("file://{}", remote.path().display()))
+        .arg(dir.path())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let repo = Repository::open(dir.path()).unwrap();
+    assert!(repo.is_shallow());
+    let err = check_history(&repo, &history, &VersionRequest::Latest, false).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::ShallowRepo(_)));
+    check_history(&repo, &history, &VersionRequest::Custom(s!("1.2.3")), false).unwrap();
+    check_history(&repo, &history, &VersionRequest::Latest, true).unwrap();
+    let next = VersionRequest::NextRelease(VersionBump::Patch);
+    check_history(&repo, &history, &next, true).unwrap();
+    let pre_release = VersionRequest::PreRelease(VersionBump::Patch);
+    let err = check_history(&repo, &history, &pre_release, true).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::ShallowRepo(_)));
+    assert!(repo.is_shallow());
+
+    let config = CromConfig::create_default(s!("v0.1.%d"), s!("Release {version}"));
+    let versions = build_versions(
+        dir.path(),
+        &config,
+        &VersionRequest::Custom(s!("v1.2.3")),
+        None,
+        &history,
+        None,
+        crate::version::today(),
+    )
+    .unwrap();
+    assert_eq!("v1.2.3", versions[0].0.to_string());
+    assert_eq!(None, versions[0].3.distance);
+
+    let fetch = HistoryOpts {
+        fetch: true,
+        ..history.clone()
+    };
+    check_history(&repo, &fetch, &VersionRequest::Latest, false).unwrap();
+    assert!(!repo.is_shallow());
+    assert!(repo.find_reference("refs/tags/v0.1.1").is_ok());
+    assert_eq!(3, git_repo::get_distance(&repo, None).unwrap());
+}
+
+/// A bare repo with `commits` commits on `master`, the first one tagged
+/// `v0.1.1`.
+#[cfg(test)]
+fn create_bare_remote(commits: usize) -> (tempfile::TempDir, Repository) {
+    let (_work_dir, work) = git_repo::create_test_repo();
+    git_repo::create_tag(&work, &Version::from(s!("v0.1.1")), "Created v0.1.1").unwrap();
+    for i in 1..commits {
+        git_repo::commit_file(&work, &format!("{}.txt", i), "change");
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let remote = Repository::init_bare(dir.path()).unwrap();
+    let url = format!("file://{}", dir.path().display());
+    let mut origin = work.remote("origin", &url).unwrap();
+    origin
+        .push(
+            &[
+                "refs/heads/master:refs/heads/master",
+                "refs/tags/v0.1.1:refs/tags/v0.1.1",
+            ],
+            None,
+        )
+        .unwrap();
+    remote.set_head("refs/heads/master").unwrap();
+
+    (dir, remote)
+}
+
+#[test]
+fn test_check_history_remote() {
+    let (_remote_dir, remote) = create_bare_remote(1);
+    let dir = tempfile::tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    let url = format!("file://{}", remote.path().display());
+    repo.remote("origin", &url).unwrap();
+
+    let history = HistoryOpts {
+        fetch: false,
+        fetch_remote: s!("origin"),
+        allow_no_tags: false,
+    };
+    assert!(check_history(&repo, &history, &VersionRequest::Latest, false).is_err());
+    check_history(&repo, &history, &VersionRequest::Latest, true).unwrap();
+    check_history(
+        &repo,
+        &history,
+        &VersionRequest::PreRelease(VersionBump::Patch),
+        true,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_build_versions_on_date() {
+    let (dir, _repo) = git_repo::create_test_repo();
+    let config = CromConfig::create_default(s!("%Y.%m.%n"), s!("Release {version}"));
+    let history = HistoryOpts {
+        fetch: false,
+        fetch_remote: s!("origin"),
+        allow_no_tags: true,
+    };
+    let today = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+
+    let versions = build_versions(
+        dir.path(),
+        &config,
+        &VersionRequest::NextRelease(VersionBump::Patch),
+        None,
+        &history,
+        None,
+        today,
+    )
+    .unwrap();
+    assert_eq!(1, versions.len());
+    assert_eq!("2024.02.0", versions[0].0.to_string());
+
+    let versions = build_versions(
+        dir.path(),
+        &config,
+        &VersionRequest::Latest,
+        None,
+        &history,
+        None,
+        today,
+    )
+    .unwrap();
+    assert_eq!(1, versions.len());
+    assert_eq!(None, versions[0].3.latest);
+    let err = check_latest(&VersionRequest::Latest, &versions).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::NoLatestVersion(_)));
+    check_latest(&VersionRequest::NextRelease(VersionBump::Patch), &versions).unwrap();
+    assert!(has_changed(dir.path(), &versions[0].2).unwrap());
+}
+
+#[test]
+fn test_describe_older_commit() {
+    let (dir, repo) = git_repo::create_test_repo();
+    let mut config = CromConfig::create_default(s!("v0.1.%d"), s!("Release {version}"));
+    config.project.tag_scope = None;
+    let history = HistoryOpts {
+        fetch: false,
+        fetch_remote: s!("origin"),
+        allow_no_tags: false,
+    };
+
+    let first = repo.head().unwrap().target().unwrap();
+    git_repo::create_tag(&repo, &Version::from(s!("v0.1.1")), "Created v0.1.1").unwrap();
+    git_repo::commit_file(&repo, "foo.txt", "foo");
+    git_repo::create_tag(&repo, &Version::from(s!("v0.1.2")), "Created v0.1.2").unwrap();
+    git_repo::commit_file(&repo, "bar.txt", "bar");
+    repo.set_head_detached(first).unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+
+    let describe = || {
+        build_versions(
+            dir.path(),
+            &config,
+            &VersionRequest::Describe,
+            None,
+            &history,
+            None,
+            crate::version::today(),
+        )
+        .unwrap()
+    };
+    assert_eq!("v0.1.1", describe()[0].0.to_string());
+
+    git_repo::commit_file(&repo, "baz.txt", "baz");
+    let versions = describe();
+    let sha: String = versions[0].3.head.chars().take(7).collect();
+    assert_eq!(format!("v0.1.1-1-g{}", sha), versions[0].0.to_string());
+    assert_eq!(Some(1), versions[0].3.distance);
+}
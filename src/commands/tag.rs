@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use git2::Repository;
+use log::info;
+
+use crate::cli::{TagArgs, VersionRequest};
+use crate::git_repo;
+use crate::github::GitHubClient;
+use crate::CromResult;
+
+pub struct TagCommand;
+
+#[async_trait]
+impl super::CommandRunner<TagArgs> for TagCommand {
+    async fn run_command(args: TagArgs) -> CromResult<i32> {
+        let request = args.sub_command.make_version_request();
+        let prerelease = matches!(request, VersionRequest::PreRelease(_));
+        let versions = super::create_versions(
+            &request,
+            args.component_opts.component.as_deref(),
+            &args.history_opts,
+            None,
+            crate::version::today(),
+        )
+        .await?;
+        super::check_latest(&request, &versions)?;
+
+        for (version, location, component, _) in versions {
+            let repo = Repository::discover(location.clone())?;
+            let message = component.project.create_tag_message(&version);
+
+            if args.local || args.push || !args.github {
+                git_repo::create_tag(&repo, &version, &message)?;
+                info!("Created tag {} locally", version);
+            }
+
+            if args.push {
+                git_repo::push_tag(&repo, &args.remote, &version)?;
+                info!("Pushed tag {} to {}", version, args.remote);
+            }
+
+            if args.github {
+                let remote_url = git_repo::get_remote_url(&repo, &args.remote)?;
+                let head = git_repo::get_head_sha(location, &repo)?;
+                let client = GitHubClient::from_remote(&args.github_opts, &remote_url)?;
+                let release = client
+                    .create_release(&version, &head, &message, prerelease)
+                    .await?;
+                info!(
+                    "Created release {} at {}",
+                    release.tag_name, release.html_url
+                );
+            }
+        }
+
+        Ok(0)
+    }
+}
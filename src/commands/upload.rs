@@ -0,0 +1,65 @@
+use async_trait::async_trait;
+use git2::Repository;
+use log::info;
+
+use super::artifact::{check_artifact_names, find_artifacts};
+use crate::cli::UploadArgs;
+use crate::git_repo;
+use crate::github::GitHubClient;
+use crate::models::{Component, ProjectArtifactTarget};
+use crate::CromResult;
+
+pub struct UploadCommand;
+
+#[async_trait]
+impl super::CommandRunner<UploadArgs> for UploadCommand {
+    async fn run_command(args: UploadArgs) -> CromResult<i32> {
+        let request = args.sub_command.make_version_request();
+        let versions = super::create_versions(
+            &request,
+            args.component_opts.component.as_deref(),
+            &args.history_opts,
+            None,
+            crate::version::today(),
+        )
+        .await?;
+        super::check_latest(&request, &versions)?;
+
+        let components: Vec<&Component> = versions.iter().map(|(_, _, c, _)| c).collect();
+        check_artifact_names(&components, args.sub_command.artifact_names())?;
+
+        // Resolve everything up front so a missing file fails before any upload
+        let mut uploads = Vec::new();
+        for (version, location, component, _) in &versions {
+            let names = component.artifact_names(args.sub_command.artifact_names());
+            if !names.is_empty() {
+                uploads.push((
+                    version,
+                    location,
+                    find_artifacts(location, &component.artifact, &names)?,
+                ));
+            }
+        }
+
+        let staging = tempfile::tempdir()?;
+        for (version, location, artifacts) in uploads {
+            let repo = Repository::discover(location)?;
+            let remote_url = git_repo::get_remote_url(&repo, &args.remote)?;
+            let client = GitHubClient::from_remote(&args.github_opts, &remote_url)?;
+            let release = client.find_release(version).await?;
+
+            for artifact in artifacts {
+                match artifact.target {
+                    ProjectArtifactTarget::GitHub => {
+                        for (name, path) in artifact.package(staging.path())? {
+                            let asset = client.upload_asset(&release, &name, &path).await?;
+                            info!("Uploaded {} to {}", asset.name, asset.browser_download_url);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(0)
+    }
+}
@@ -15,8 +15,24 @@ impl super::CommandRunner<UtilityArgs> for UtilsCommand {
         match args.sub_command {
             UtilitySubCommand::VerifyNoChanges => {
                 let repo = Repository::discover(env::current_dir()?)?;
-                git_repo::is_working_repo_clean(&repo).map(|x| if x { 0 } else { 1 })
+                verify_no_changes(&repo)
             }
         }
     }
 }
+
+fn verify_no_changes(repo: &Repository) -> CromResult<i32> {
+    git_repo::is_working_repo_clean(repo).map(|x| if x { 0 } else { 1 })
+}
+
+#[test]
+fn test_verify_no_changes() {
+    let (dir, repo) = git_repo::create_test_repo();
+    assert_eq!(0, verify_no_changes(&repo).unwrap());
+
+    std::fs::write(dir.path().join("new.txt"), "untracked").unwrap();
+    assert_eq!(0, verify_no_changes(&repo).unwrap());
+
+    std::fs::write(dir.path().join("README.md"), "changed").unwrap();
+    assert_eq!(1, verify_no_changes(&repo).unwrap());
+}
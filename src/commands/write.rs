@@ -8,12 +8,16 @@ use std::io::prelude::*;
 use std::process::*;
 
 use ini::Ini;
+use regex::{Captures, Regex};
 use serde_json::{self, Value};
 use toml_edit::{value, Document};
 
 use crate::cli::WriteArgs;
 use crate::errors::ErrorKind;
-use crate::models::{CargoConfig, MavenConfig, NodeConfig, PropertyFileConfig, VersionPyConfig};
+use crate::models::{
+    CargoConfig, GradleConfig, MavenConfig, MavenMode, NodeConfig, PropertyFileConfig,
+    PyProjectConfig, VersionPyConfig,
+};
 use crate::version::Version;
 use crate::CromResult;
 
@@ -22,27 +26,47 @@ pub struct WriteCommand;
 #[async_trait]
 impl super::CommandRunner<WriteArgs> for WriteCommand {
     async fn run_command(args: WriteArgs) -> CromResult<i32> {
-        let (version, location, config) =
-            super::create_version(args.sub_command.make_version_request()).await?;
+        let request = args.sub_command.make_version_request();
+        let versions = super::create_versions(
+            &request,
+            args.component_opts.component.as_deref(),
+            &args.history_opts,
+            None,
+            crate::version::today(),
+        )
+        .await?;
+        super::check_latest(&request, &versions)?;
+
+        for (version, location, component, _) in versions {
+            let project = component.project;
+
+            if let Some(project) = project.cargo {
+                project.update_version(location.clone(), &version)?;
+            }
 
-        if let Some(project) = config.project.cargo {
-            project.update_version(location.clone(), &version)?;
-        }
+            if let Some(project) = project.property {
+                project.update_version(location.clone(), &version)?;
+            }
 
-        if let Some(project) = config.project.property {
-            project.update_version(location.clone(), &version)?;
-        }
+            if let Some(project) = project.maven {
+                project.update_version(location.clone(), &version)?;
+            }
 
-        if let Some(project) = config.project.maven {
-            project.update_version(location.clone(), &version)?;
-        }
+            if let Some(project) = project.gradle {
+                project.update_version(location.clone(), &version)?;
+            }
 
-        if let Some(project) = config.project.package_json {
-            project.update_version(location.clone(), &version)?;
-        }
+            if let Some(project) = project.package_json {
+                project.update_version(location.clone(), &version)?;
+            }
 
-        if let Some(project) = config.project.version_py {
-            project.update_version(location, &version)?;
+            if let Some(project) = project.version_py {
+                project.update_version(location.clone(), &version)?;
+            }
+
+            if let Some(project) = project.pyproject {
+                project.update_version(location, &version)?;
+            }
         }
 
         Ok(0)
@@ -147,39 +171,71 @@ impl UpdateVersion for NodeConfig {
 
 impl UpdateVersion for MavenConfig {
     fn update_version(&self, root_path: PathBuf, version: &Version) -> CromResult<()> {
-        let spawn = Command::new("mvn")
-            .current_dir(root_path)
-            .args(&[
-                "versions:set",
-                &format!("-DnewVersion={}", version),
-                "-DprocessAllModules=true",
-            ])
-            .spawn();
-
-        let mut child = match spawn {
-            Ok(child) => child,
-            Err(e) => {
-                bail!(ErrorKind::Maven(e.to_string(),))
-            }
+        let mut working_dir = root_path;
+        if let Some(directory) = &self.directory {
+            working_dir.push(directory);
+        }
+
+        if self.mode == MavenMode::Native {
+            return crate::pom::update_versions(
+                &working_dir.join(&self.path),
+                &version.to_string(),
+            );
+        }
+
+        // A wrapper like `./mvnw` lives in the project, not on the PATH.
+        let executable = if self.executable.contains('/') {
+            working_dir.join(&self.executable)
+        } else {
+            PathBuf::from(&self.executable)
         };
 
-        let ecode = match child.wait() {
-            Ok(code) => code,
+        let output = Command::new(&executable)
+            .current_dir(&working_dir)
+            .args(self.versions_set_args(&version.to_string()))
+            .output();
+
+        let output = match output {
+            Ok(output) => output,
             Err(e) => {
-                bail!(ErrorKind::Maven(e.to_string(),))
+                bail!(ErrorKind::Maven(format!(
+                    "Unable to run {:?}: {}",
+                    executable, e
+                )))
             }
         };
 
-        if !ecode.success() {
-            bail!(ErrorKind::Maven(
-                "Maven wasn't able to set version".to_string()
-            ))
+        if !output.status.success() {
+            bail!(ErrorKind::Maven(format!(
+                "Maven wasn't able to set version ({})\n{}{}",
+                output.status,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )))
         } else {
             Ok(())
         }
     }
 }
 
+impl UpdateVersion for GradleConfig {
+    fn update_version(&self, root_path: PathBuf, version: &Version) -> CromResult<()> {
+        let mut path = root_path;
+        if let Some(dir) = &self.directory {
+            path.push(dir);
+        }
+
+        let version = version.to_string();
+        crate::gradle::update_properties(&path, &version)?;
+
+        if self.build_script {
+            crate::gradle::update_build_scripts(&path, &version)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl UpdateVersion for PropertyFileConfig {
     fn update_version(&self, root_path: PathBuf, version: &Version) -> CromResult<()> {
         let mut path = root_path;
@@ -208,10 +264,212 @@ impl UpdateVersion for VersionPyConfig {
         let mut path = root_path;
         path.push(self.path.clone());
 
-        let version_text = format!("__version__ = \"{}\"", version);
+        let version_text = if path.exists() {
+            let text = read_to_string(&path)?;
+            update_version_in_version_py(&text, &self.variable, &version.to_string())
+        } else {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            format!("{} = \"{}\"\n", self.variable, version)
+        };
 
         let mut file = File::create(path)?;
         file.write_all(version_text.as_bytes())?;
         Ok(())
     }
 }
+
+/// Replace the value assigned to `variable`, keeping its quotes and the rest
+/// of the file. The assignment is added to the end when it's missing.
+fn update_version_in_version_py(text: &str, variable: &str, version: &str) -> String {
+    let assignment = Regex::new(&format!(
+        r#"(?m)^({}\s*(?::\s*\w+\s*)?=\s*)("[^"\n]*"|'[^'\n]*')"#,
+        regex::escape(variable)
+    ))
+    .unwrap();
+
+    if assignment.is_match(text) {
+        return assignment
+            .replace(text, |captures: &Captures| {
+                let quote = &captures[2][..1];
+                format!("{}{}{}{}", &captures[1], quote, version, quote)
+            })
+            .to_string();
+    }
+
+    let mut text = text.to_string();
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(&format!("{} = \"{}\"\n", variable, version));
+    text
+}
+
+impl UpdateVersion for PyProjectConfig {
+    fn update_version(&self, root_path: PathBuf, version: &Version) -> CromResult<()> {
+        let mut path = root_path;
+        if let Some(dir) = &self.directory {
+            path.push(dir);
+        }
+        path.push(crate::statics::PYPROJECT_TOML);
+
+        let text = read_to_string(&path)?;
+        let toml_string = update_version_in_pyproject(&text, &version.pep440())?;
+
+        let mut file = File::create(path)?;
+        file.write_all(toml_string.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Set the version in a `pyproject.toml`, in `[project]` when it's a PEP 621
+/// project and in `[tool.poetry]` when it's a Poetry one.
+fn update_version_in_pyproject(text: &str, version: &str) -> CromResult<String> {
+    let mut doc = match text.parse::<Document>() {
+        Ok(doc) => doc,
+        Err(e) => bail!(ErrorKind::InvalidToml(format!(
+            "pyproject.toml was not valid: {}",
+            e
+        ))),
+    };
+
+    let project = doc
+        .as_table()
+        .get("project")
+        .and_then(|item| item.as_table());
+    let poetry = doc
+        .as_table()
+        .get("tool")
+        .and_then(|item| item.as_table())
+        .and_then(|tool| tool.get("poetry"))
+        .and_then(|item| item.as_table());
+
+    let has_version = project
+        .map(|project| project.contains_key("version"))
+        .unwrap_or(false);
+    let is_dynamic = project
+        .and_then(|project| project.get("dynamic"))
+        .and_then(|item| item.as_array())
+        .map(|dynamic| dynamic.iter().any(|item| item.as_str() == Some("version")))
+        .unwrap_or(false);
+
+    match (project.is_some(), poetry.is_some()) {
+        (true, _) if has_version => doc["project"]["version"] = value(version),
+        (_, true) => doc["tool"]["poetry"]["version"] = value(version),
+        (true, false) if is_dynamic => bail!(ErrorKind::InvalidToml(
+            "pyproject.toml has a dynamic version, it can't be updated.".to_string()
+        )),
+        (true, false) => doc["project"]["version"] = value(version),
+        (false, false) => bail!(ErrorKind::InvalidToml(
+            "pyproject.toml has no [project] or [tool.poetry] table.".to_string()
+        )),
+    }
+
+    Ok(doc.to_string())
+}
+
+#[test]
+fn test_update_version_in_pyproject() {
+    let pep621 = "[project]\nname = \"crom\" # the name\nversion = \"0.1.0\"\n";
+    assert_eq!(
+        "[project]\nname = \"crom\" # the name\nversion = \"0.2.0rc1\"\n",
+        update_version_in_pyproject(pep621, "0.2.0rc1").unwrap()
+    );
+
+    let poetry =
+        "[tool.poetry]\nname = \"crom\"\nversion = \"0.1.0\"\n\n[tool.black]\nline-length = 100\n";
+    assert_eq!(
+        "[tool.poetry]\nname = \"crom\"\nversion = \"0.2.0\"\n\n[tool.black]\nline-length = 100\n",
+        update_version_in_pyproject(poetry, "0.2.0").unwrap()
+    );
+
+    let dynamic = "[project]\nname = \"crom\"\ndynamic = [\"version\"]\n";
+    assert!(update_version_in_pyproject(dynamic, "0.2.0").is_err());
+    assert!(update_version_in_pyproject("[tool.black]\n", "0.2.0").is_err());
+}
+
+#[test]
+fn test_update_version_in_version_py() {
+    let text = "\"\"\"Crom's version.\"\"\"\n\n__all__ = ['__version__']\n__version__ = '0.1.0'\n";
+    assert_eq!(
+        "\"\"\"Crom's version.\"\"\"\n\n__all__ = ['__version__']\n__version__ = '0.2.0'\n",
+        update_version_in_version_py(text, "__version__", "0.2.0")
+    );
+
+    assert_eq!(
+        "VERSION: str = \"0.2.0\"\n",
+        update_version_in_version_py("VERSION: str = \"0.1.0\"\n", "VERSION", "0.2.0")
+    );
+    assert_eq!(
+        "import os\nVERSION = \"0.2.0\"\n",
+        update_version_in_version_py("import os", "VERSION", "0.2.0")
+    );
+}
+
+#[test]
+fn test_create_version_py() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = VersionPyConfig {
+        path: s!("src/crom/_version.py"),
+        variable: s!("__version__"),
+    };
+
+    config
+        .update_version(dir.path().to_path_buf(), &Version::from(s!("1.2.3")))
+        .unwrap();
+    assert_eq!(
+        "__version__ = \"1.2.3\"\n",
+        read_to_string(dir.path().join("src/crom/_version.py")).unwrap()
+    );
+
+    config
+        .update_version(dir.path().to_path_buf(), &Version::from(s!("1.2.4")))
+        .unwrap();
+    assert_eq!(
+        "__version__ = \"1.2.4\"\n",
+        read_to_string(dir.path().join("src/crom/_version.py")).unwrap()
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_maven_failure_output() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let wrapper = dir.path().join("mvnw");
+    std::fs::write(
+        &wrapper,
+        "#!/bin/sh\necho \"running $3 $4\"\necho \"BUILD FAILURE\" >&2\nexit 1\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&wrapper, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut config = MavenConfig {
+        directory: None,
+        path: s!("pom.xml"),
+        mode: MavenMode::Mvn,
+        executable: s!("./mvnw"),
+        args: Vec::new(),
+        profiles: Vec::new(),
+    };
+    let version = Version::from(s!("1.2.3"));
+
+    let err = config
+        .update_version(dir.path().to_path_buf(), &version)
+        .unwrap_err();
+    match err.kind() {
+        ErrorKind::Maven(output) => {
+            assert!(output.contains("running versions:set -DnewVersion=1.2.3"));
+            assert!(output.contains("BUILD FAILURE"));
+        }
+        kind => panic!("unexpected error {:?}", kind),
+    }
+
+    config.executable = s!("./missing-mvnw");
+    let err = config
+        .update_version(dir.path().to_path_buf(), &version)
+        .unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::Maven(_)));
+}
@@ -29,16 +29,51 @@ error_chain! {
             display("There was an error when reading .crom.toml. Error: '{}'", t)
         }
 
+        InvalidPreReleaseTemplate(t: String) {
+            description("pre-release-template was not valid")
+            display("The pre-release-template in .crom.toml is not valid: {}", t)
+        }
+
+        InvalidBump(t: String) {
+            description("Unable to bump version")
+            display("Unable to bump version. Error: '{}'", t)
+        }
+
         UnableToTag(t: String) {
             description("Unable to create tag.")
             display("Unable to create tag. Error: '{}'", t)
         }
 
+        UnableToPush(t: String) {
+            description("Unable to push to remote.")
+            display("Unable to push to remote. Error: '{}'", t)
+        }
+
         UnknownGitRemotes(t: String) {
             description("Unable to find git remote")
             display("Unable to determine git repo from remote: {}", t)
         }
 
+        ShallowRepo(t: ::std::path::PathBuf) {
+            description("Repo is a shallow clone")
+            display("The repo at {} is a shallow clone, so the tags needed to compute a version are missing. Use `--fetch`, run `git fetch --unshallow --tags`, or clone the full history (e.g. `fetch-depth: 0` with actions/checkout).", t.display())
+        }
+
+        UnableToFetch(t: String) {
+            description("Unable to fetch from the remote")
+            display("{}", t)
+        }
+
+        NoLatestVersion(t: String) {
+            description("No latest version")
+            display("There is no latest version, no tags match {}. Use next-release or custom for the first release.", t)
+        }
+
+        NoTagsFound(t: ::std::path::PathBuf) {
+            description("No tags found")
+            display("The repo at {} has no tags, so the version would start over. Fetch the tags with `--fetch` or `git fetch --tags`, or use `--allow-no-tags` before the first release.", t.display())
+        }
+
         UnknownGitHead(t: ::std::path::PathBuf) {
             description("Unable to decode head")
             display("Unable to decode head from repo {}", t.display())
@@ -69,6 +104,11 @@ error_chain! {
             display("{}", t)
         }
 
+        InvalidXml(t: String) {
+            description("Invalid XML")
+            display("{}", t)
+        }
+
         Maven(t: String) {
             description("Error when executing Maven")
             display("{}", t)
@@ -1,32 +1,107 @@
 use crate::errors::ErrorKind;
 use error_chain::bail;
 use git2::*;
-use log::debug;
+use glob::{MatchOptions, Pattern};
+use log::{debug, warn};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
 use std::path::PathBuf;
 use std::vec::Vec;
 
 use crate::errors::Error as CromError;
+use crate::models::TagScope;
 use crate::version::{Version, VersionMatcher};
 
 type Result<T> = std::result::Result<T, CromError>;
 
-pub fn get_tags(repo: &Repository, matcher: &VersionMatcher) -> Result<Vec<Version>> {
+pub fn get_tags(
+    repo: &Repository,
+    matcher: &VersionMatcher,
+    scope: &TagScope,
+) -> Result<Vec<Version>> {
     let tags = repo.tag_names(None)?;
-    let mut tags: Vec<Version> = tags
+    let tags: Vec<Version> = tags
         .iter()
         .map(|x| x.unwrap().to_string())
         .flat_map(|version| matcher.match_version(version))
         .collect();
 
+    let mut tags = match scope {
+        TagScope::All => tags,
+        TagScope::Reachable => {
+            let head = repo.head()?.peel_to_commit()?.id();
+            let mut reachable = Vec::new();
+            for tag in tags {
+                if is_reachable(repo, head, &tag)? {
+                    reachable.push(tag);
+                } else {
+                    debug!("Skipping {} since it isn't reachable from HEAD", tag);
+                }
+            }
+            reachable
+        }
+    };
+
     tags.sort();
 
     debug!("Tags discovered: {:?}", tags);
     Ok(tags)
 }
 
+/// The commit that `version`'s tag points at.
+pub fn find_tag_commit<'a>(repo: &'a Repository, version: &Version) -> Result<Commit<'a>> {
+    Ok(repo
+        .find_reference(&format!("refs/tags/{}", version))?
+        .peel_to_commit()?)
+}
+
+fn is_reachable(repo: &Repository, head: Oid, version: &Version) -> Result<bool> {
+    let commit = find_tag_commit(repo, version)?.id();
+
+    Ok(commit == head || repo.graph_descendant_of(head, commit)?)
+}
+
+/// The `versions` whose commit HEAD contains, the tags `git describe` would
+/// use. Commits missing from the local repo, e.g. for tags read from a
+/// remote, are left out.
+pub fn reachable_versions(
+    repo: &Repository,
+    versions: &[(Version, Oid)],
+) -> Result<Vec<(Version, Oid)>> {
+    let head = repo.head()?.peel_to_commit()?.id();
+    let mut reachable = Vec::new();
+    for (version, commit) in versions {
+        if repo.find_commit(*commit).is_err() {
+            continue;
+        }
+
+        if *commit == head || repo.graph_descendant_of(head, *commit)? {
+            reachable.push((version.clone(), *commit));
+        }
+    }
+
+    Ok(reachable)
+}
+
+/// The newest of `versions` whose tag points at HEAD.
+///
+/// `versions` are pairs of a version and the commit its tag points at.
+pub fn get_head_version(repo: &Repository, versions: &[(Version, Oid)]) -> Result<Option<Version>> {
+    let head = repo.head()?.peel_to_commit()?.id();
+    Ok(versions
+        .iter()
+        .rev()
+        .find(|(_, commit)| *commit == head)
+        .map(|(version, _)| version.clone()))
+}
+
+/// True when no tracked file has changes, staged or not. Untracked files
+/// are ignored. Used by `crom util verify-no-changes` and for the `dirty`
+/// marker of `crom get current --dirty`.
 pub fn is_working_repo_clean(repo: &Repository) -> Result<bool> {
     let mut options = StatusOptions::new();
-    let options = options.include_unmodified(true).include_untracked(false);
+    let options = options.include_unmodified(false).include_untracked(false);
     let statuses = repo.statuses(Some(options))?;
     Ok(statuses.is_empty())
 }
@@ -48,3 +123,536 @@ pub fn get_head_sha(location: PathBuf, repo: &Repository) -> Result<String> {
         .collect();
     Ok(strs.join(""))
 }
+
+/// Short name of the current branch, or `HEAD` when detached.
+pub fn get_branch_name(repo: &Repository) -> Result<String> {
+    let head = repo.head()?;
+    match head.shorthand() {
+        Some(name) if head.is_branch() => Ok(name.to_string()),
+        _ => Ok(s!("HEAD")),
+    }
+}
+
+/// Commit time of HEAD, in seconds since the epoch.
+pub fn get_head_time(repo: &Repository) -> Result<i64> {
+    Ok(repo.head()?.peel_to_commit()?.time().seconds())
+}
+
+/// Number of commits reachable from HEAD that are not reachable from `since`.
+///
+/// When there is no commit, or it isn't in the local repo, every commit
+/// reachable from HEAD is counted.
+pub fn get_distance(repo: &Repository, since: Option<Oid>) -> Result<usize> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+
+    if let Some(since) = since {
+        if repo.find_commit(since).is_ok() {
+            revwalk.hide(since)?;
+        } else {
+            warn!(
+                "Commit {} is not in the local repo, counting every commit",
+                since
+            );
+        }
+    }
+
+    let mut distance = 0;
+    for oid in revwalk {
+        oid?;
+        distance += 1;
+    }
+
+    Ok(distance)
+}
+
+/// Whether any file matching `patterns` differs between `version`'s tag and HEAD.
+///
+/// When there is no tag, everything is considered changed. A `*` in a
+/// pattern doesn't match `/`, use `**` to match across directories.
+pub fn has_changes(
+    repo: &Repository,
+    version: Option<&Version>,
+    patterns: &[Pattern],
+) -> Result<bool> {
+    let version = match version {
+        Some(version) => version,
+        None => return Ok(true),
+    };
+
+    let tag_tree = find_tag_commit(repo, version)?.tree()?;
+    let head_tree = repo.head()?.peel_to_commit()?.tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&tag_tree), Some(&head_tree), None)?;
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::default()
+    };
+
+    for delta in diff.deltas() {
+        let paths = [delta.old_file().path(), delta.new_file().path()];
+        for path in paths.iter().flatten() {
+            if patterns
+                .iter()
+                .any(|pattern| pattern.matches_path_with(path, options))
+            {
+                debug!("{:?} changed since {}", path, version);
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+pub fn get_remote_url(repo: &Repository, remote_name: &str) -> Result<String> {
+    let remote = find_remote(repo, remote_name)?;
+
+    match remote.url() {
+        Some(url) => Ok(url.to_string()),
+        None => bail!(ErrorKind::UnknownGitRemotes(remote_name.to_string())),
+    }
+}
+
+pub fn create_tag(repo: &Repository, version: &Version, message: &str) -> Result<Oid> {
+    let head = repo.head()?.peel_to_commit()?;
+    let signature = match repo.signature() {
+        Ok(signature) => signature,
+        Err(e) => bail!(ErrorKind::UnableToTag(format!(
+            "Unable to determine tagger, is user.name and user.email set? {}",
+            e.message()
+        ))),
+    };
+
+    match repo.tag(
+        &version.to_string(),
+        head.as_object(),
+        &signature,
+        message,
+        false,
+    ) {
+        Ok(oid) => {
+            debug!("Created tag {} at {}", version, head.id());
+            Ok(oid)
+        }
+        Err(e) => bail!(ErrorKind::UnableToTag(e.message().to_string())),
+    }
+}
+
+pub fn push_tag(repo: &Repository, remote_name: &str, version: &Version) -> Result<()> {
+    let mut remote = find_remote(repo, remote_name)?;
+
+    let refspec = format!("refs/tags/{0}:refs/tags/{0}", version);
+    let rejection: RefCell<Option<String>> = RefCell::new(None);
+
+    let mut callbacks = create_remote_callbacks(repo)?;
+    callbacks.push_update_reference(|name, status| {
+        if let Some(message) = status {
+            rejection.replace(Some(format!("{} was rejected: {}", name, message)));
+        }
+        Ok(())
+    });
+
+    let mut options = PushOptions::new();
+    options.remote_callbacks(callbacks);
+
+    if let Err(e) = remote.push(&[&refspec], Some(&mut options)) {
+        bail!(ErrorKind::UnableToPush(e.message().to_string()));
+    }
+
+    if let Some(message) = rejection.take() {
+        bail!(ErrorKind::UnableToPush(message));
+    }
+
+    debug!("Pushed {} to {}", refspec, remote_name);
+    Ok(())
+}
+
+/// Tags on `remote_name` that match, along with the commit they point at,
+/// without fetching them.
+pub fn get_remote_tags(
+    repo: &Repository,
+    remote_name: &str,
+    matcher: &VersionMatcher,
+) -> Result<Vec<(Version, Oid)>> {
+    let mut remote = find_remote(repo, remote_name)?;
+    let connection =
+        remote.connect_auth(Direction::Fetch, Some(create_remote_callbacks(repo)?), None)?;
+
+    // Annotated tags are listed twice, the `^{}` entry is the commit they point at
+    let mut commits: HashMap<String, Oid> = HashMap::new();
+    for head in connection.list()? {
+        let name = match head.name().strip_prefix("refs/tags/") {
+            Some(name) => name,
+            None => continue,
+        };
+
+        match name.strip_suffix("^{}") {
+            Some(name) => {
+                commits.insert(name.to_string(), head.oid());
+            }
+            None => {
+                commits
+                    .entry(name.to_string())
+                    .or_insert_with(|| head.oid());
+            }
+        }
+    }
+
+    let mut tags: Vec<(Version, Oid)> = commits
+        .into_iter()
+        .flat_map(|(name, oid)| matcher.match_version(name).map(|version| (version, oid)))
+        .collect();
+    tags.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+    debug!("Tags discovered on {}: {:?}", remote_name, tags);
+    Ok(tags)
+}
+
+fn find_remote<'a>(repo: &'a Repository, remote_name: &str) -> Result<Remote<'a>> {
+    match repo.find_remote(remote_name) {
+        Ok(remote) => Ok(remote),
+        Err(e) => bail!(ErrorKind::UnknownGitRemotes(format!(
+            "{} ({})",
+            remote_name,
+            e.message()
+        ))),
+    }
+}
+
+/// Fetch the full history of a shallow clone from `remote_name`.
+///
+/// libgit2 can't deepen a shallow clone, so this runs `git fetch --unshallow`.
+pub fn unshallow(repo: &Repository, remote_name: &str) -> Result<()> {
+    find_remote(repo, remote_name)?;
+
+    let directory = repo.workdir().unwrap_or_else(|| repo.path());
+    let output = std::process::Command::new("git")
+        .current_dir(directory)
+        .args(["fetch", "--unshallow", "--tags", remote_name])
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            debug!("Fetched the full history from {}", remote_name);
+            Ok(())
+        }
+        Ok(output) => bail!(ErrorKind::UnableToFetch(format!(
+            "git fetch --unshallow failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))),
+        Err(e) => bail!(ErrorKind::UnableToFetch(format!(
+            "Unable to run git fetch --unshallow: {}",
+            e
+        ))),
+    }
+}
+
+/// Fetch every tag from `remote_name`.
+pub fn fetch_tags(repo: &Repository, remote_name: &str) -> Result<()> {
+    let mut remote = find_remote(repo, remote_name)?;
+
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(create_remote_callbacks(repo)?);
+    options.download_tags(AutotagOption::All);
+
+    remote.fetch(&["+refs/tags/*:refs/tags/*"], Some(&mut options), None)?;
+
+    debug!("Fetched tags from {}", remote_name);
+    Ok(())
+}
+
+/// Callbacks used when talking to a remote.
+///
+/// Credentials are tried in order: the SSH agent, a token from the
+/// environment (see `GIT_TOKEN_ENV_VARS`), then the git credential helper.
+pub fn create_remote_callbacks(repo: &Repository) -> Result<RemoteCallbacks<'static>> {
+    let config = repo.config()?;
+    let mut tried_agent = false;
+    let mut tried_token = false;
+    let mut tried_helper = false;
+
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        if allowed.contains(CredentialType::USERNAME) {
+            return Cred::username(username.unwrap_or("git"));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) && !tried_agent {
+            tried_agent = true;
+            debug!("Using ssh-agent for {}", url);
+            return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if !tried_token {
+                tried_token = true;
+                let token = crate::statics::GIT_TOKEN_ENV_VARS
+                    .iter()
+                    .find_map(|name| env::var(name).ok());
+
+                if let Some(token) = token {
+                    debug!("Using token from environment for {}", url);
+                    return Cred::userpass_plaintext(username.unwrap_or("x-access-token"), &token);
+                }
+            }
+
+            if !tried_helper {
+                tried_helper = true;
+                debug!("Using credential helper for {}", url);
+                return Cred::credential_helper(&config, url, username);
+            }
+        }
+
+        Err(git2::Error::from_str(&format!(
+            "No credentials available for {}",
+            url
+        )))
+    });
+
+    Ok(callbacks)
+}
+
+#[cfg(test)]
+pub fn create_test_repo() -> (tempfile::TempDir, Repository) {
+    let dir = tempfile::tempdir().unwrap();
+    let repo = Repository::init(dir.path()).unwrap();
+    {
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Crom Test").unwrap();
+        config.set_str("user.email", "crom@example.com").unwrap();
+    }
+
+    commit_file(&repo, "README.md", "init");
+    (dir, repo)
+}
+
+#[cfg(test)]
+pub fn commit_file(repo: &Repository, name: &str, contents: &str) -> Oid {
+    let workdir = repo.workdir().unwrap();
+    let path = workdir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+
+    let mut index = repo.index().unwrap();
+    index.add_path(std::path::Path::new(name)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+    let signature = repo.signature().unwrap();
+    let parent = repo.head().ok().map(|head| head.peel_to_commit().unwrap());
+    let parents: Vec<&Commit> = parent.iter().collect();
+
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        &format!("Update {}", name),
+        &tree,
+        &parents,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_create_tag() {
+    let (_dir, repo) = create_test_repo();
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let version = matcher.match_version(s!("v0.1.1")).unwrap();
+
+    create_tag(&repo, &version, "Created v0.1.1").unwrap();
+
+    let tag = repo
+        .find_reference("refs/tags/v0.1.1")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(Some("Created v0.1.1"), tag.message());
+    assert_eq!(repo.head().unwrap().target().unwrap(), tag.target_id());
+    assert_eq!(
+        vec![version.clone()],
+        get_tags(&repo, &matcher, &TagScope::All).unwrap()
+    );
+
+    let err = create_tag(&repo, &version, "Created v0.1.1").unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnableToTag(_)));
+}
+
+#[test]
+fn test_remote_transports() {
+    let version = git2::Version::get();
+    assert!(
+        version.https(),
+        "git2 needs the https feature for GitHub remotes"
+    );
+    assert!(version.ssh(), "git2 needs the ssh feature for ssh remotes");
+}
+
+#[test]
+fn test_push_tag() {
+    let (_dir, repo) = create_test_repo();
+    let remote_dir = tempfile::tempdir().unwrap();
+    let remote_repo = Repository::init_bare(remote_dir.path()).unwrap();
+    let remote_url = format!("file://{}", remote_dir.path().display());
+    repo.remote("origin", &remote_url).unwrap();
+
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let version = matcher.match_version(s!("v0.1.1")).unwrap();
+    create_tag(&repo, &version, "Created v0.1.1").unwrap();
+    push_tag(&repo, "origin", &version).unwrap();
+
+    let remote_tag = remote_repo
+        .find_reference("refs/tags/v0.1.1")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(repo.head().unwrap().target().unwrap(), remote_tag.id());
+
+    repo.tag_delete("v0.1.1").unwrap();
+    commit_file(&repo, "foo.txt", "foo");
+    create_tag(&repo, &version, "Created v0.1.1 again").unwrap();
+
+    let err = push_tag(&repo, "origin", &version).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnableToPush(_)));
+
+    let err = push_tag(&repo, "upstream", &version).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::UnknownGitRemotes(_)));
+}
+
+#[test]
+fn test_get_distance() {
+    let (_dir, repo) = create_test_repo();
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let version = matcher.match_version(s!("v0.1.1")).unwrap();
+
+    assert_eq!(1, get_distance(&repo, None).unwrap());
+
+    create_tag(&repo, &version, "Created v0.1.1").unwrap();
+    let tag_commit = find_tag_commit(&repo, &version).unwrap().id();
+    assert_eq!(0, get_distance(&repo, Some(tag_commit)).unwrap());
+
+    commit_file(&repo, "foo.txt", "foo");
+    commit_file(&repo, "bar.txt", "bar");
+    assert_eq!(2, get_distance(&repo, Some(tag_commit)).unwrap());
+    assert_eq!(3, get_distance(&repo, None).unwrap());
+    assert_eq!(3, get_distance(&repo, Some(Oid::zero())).unwrap());
+}
+
+#[test]
+fn test_get_reachable_tags() {
+    let (_dir, repo) = create_test_repo();
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let main = repo.head().unwrap().peel_to_commit().unwrap();
+    let main_branch = get_branch_name(&repo).unwrap();
+
+    let v1 = matcher.match_version(s!("v0.1.1")).unwrap();
+    create_tag(&repo, &v1, "Created v0.1.1").unwrap();
+
+    repo.branch("hotfix", &main, false).unwrap();
+    repo.set_head("refs/heads/hotfix").unwrap();
+    commit_file(&repo, "hotfix.txt", "hotfix");
+    let v2 = matcher.match_version(s!("v0.1.2")).unwrap();
+    create_tag(&repo, &v2, "Created v0.1.2").unwrap();
+
+    repo.set_head(&format!("refs/heads/{}", main_branch))
+        .unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+    commit_file(&repo, "main.txt", "main");
+
+    assert_eq!(
+        vec![v1.clone(), v2],
+        get_tags(&repo, &matcher, &TagScope::All).unwrap()
+    );
+    assert_eq!(
+        vec![v1],
+        get_tags(&repo, &matcher, &TagScope::Reachable).unwrap()
+    );
+}
+
+#[test]
+fn test_has_changes() {
+    let (_dir, repo) = create_test_repo();
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let version = matcher.match_version(s!("v0.1.1")).unwrap();
+    let patterns = vec![Pattern::new("server/**").unwrap()];
+
+    assert!(has_changes(&repo, None, &patterns).unwrap());
+
+    commit_file(&repo, "server/main.rs", "fn main() {}");
+    create_tag(&repo, &version, "Created v0.1.1").unwrap();
+    assert!(!has_changes(&repo, Some(&version), &patterns).unwrap());
+
+    commit_file(&repo, "ui/index.js", "");
+    assert!(!has_changes(&repo, Some(&version), &patterns).unwrap());
+
+    commit_file(&repo, "server/main.rs", "fn main() { }");
+    assert!(has_changes(&repo, Some(&version), &patterns).unwrap());
+
+    let patterns = vec![Pattern::new("*.toml").unwrap()];
+    commit_file(&repo, "server/Cargo.toml", "[package]");
+    assert!(!has_changes(&repo, Some(&version), &patterns).unwrap());
+    commit_file(&repo, "Cargo.toml", "[package]");
+    assert!(has_changes(&repo, Some(&version), &patterns).unwrap());
+}
+
+#[test]
+fn test_get_head_version() {
+    let (_dir, repo) = create_test_repo();
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let v1 = matcher.match_version(s!("v0.1.1")).unwrap();
+    let v2 = matcher.match_version(s!("v0.1.2")).unwrap();
+
+    create_tag(&repo, &v1, "Created v0.1.1").unwrap();
+    create_tag(&repo, &v2, "Created v0.1.2").unwrap();
+    let head = repo.head().unwrap().target().unwrap();
+    let versions = vec![(v1, head), (v2.clone(), head)];
+    assert_eq!(Some(v2), get_head_version(&repo, &versions).unwrap());
+
+    commit_file(&repo, "foo.txt", "foo");
+    assert_eq!(None, get_head_version(&repo, &versions).unwrap());
+}
+
+#[test]
+fn test_is_working_repo_clean() {
+    let (dir, repo) = create_test_repo();
+    assert!(is_working_repo_clean(&repo).unwrap());
+
+    std::fs::write(dir.path().join("README.md"), "changed").unwrap();
+    assert!(!is_working_repo_clean(&repo).unwrap());
+}
+
+#[test]
+fn test_get_remote_tags() {
+    let (_remote_dir, remote_repo) = create_test_repo();
+    let matcher = VersionMatcher::new("v0.1.%d");
+    let v1 = matcher.match_version(s!("v0.1.1")).unwrap();
+    let v2 = matcher.match_version(s!("v0.1.2")).unwrap();
+
+    create_tag(&remote_repo, &v1, "Created v0.1.1").unwrap();
+    let v2_commit = commit_file(&remote_repo, "foo.txt", "foo");
+    remote_repo
+        .tag_lightweight(
+            "v0.1.2",
+            &remote_repo.find_object(v2_commit, None).unwrap(),
+            false,
+        )
+        .unwrap();
+    remote_repo
+        .tag_lightweight(
+            "other",
+            &remote_repo.find_object(v2_commit, None).unwrap(),
+            false,
+        )
+        .unwrap();
+
+    let (_dir, repo) = create_test_repo();
+    let url = format!("file://{}", remote_repo.workdir().unwrap().display());
+    repo.remote("origin", &url).unwrap();
+
+    let v1_commit = find_tag_commit(&remote_repo, &v1).unwrap().id();
+    assert_eq!(
+        vec![(v1, v1_commit), (v2, v2_commit)],
+        get_remote_tags(&repo, "origin", &matcher).unwrap()
+    );
+    assert!(repo.tag_names(None).unwrap().is_empty());
+}
@@ -0,0 +1,321 @@
+use error_chain::bail;
+use log::debug;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE, USER_AGENT};
+use reqwest::{Client, Response};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::cli::GitHubOpts;
+use crate::errors::ErrorKind;
+use crate::version::Version;
+use crate::CromResult;
+
+#[derive(Serialize, Debug)]
+struct CreateRelease {
+    tag_name: String,
+    target_commitish: String,
+    name: String,
+    body: String,
+    draft: bool,
+    prerelease: bool,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitHubRelease {
+    pub tag_name: String,
+    pub html_url: String,
+    pub upload_url: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct GitHubAsset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+pub struct GitHubClient {
+    client: Client,
+    api_url: String,
+    owner: String,
+    repo: String,
+}
+
+impl GitHubClient {
+    pub fn new(api_url: &str, token: &str, owner: &str, repo: &str) -> CromResult<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github.v3+json"),
+        );
+        headers.insert(
+            USER_AGENT,
+            HeaderValue::from_static(concat!("crom/", env!("CARGO_PKG_VERSION"))),
+        );
+
+        match HeaderValue::from_str(&format!("token {}", token)) {
+            Ok(value) => headers.insert(AUTHORIZATION, value),
+            Err(e) => bail!(ErrorKind::HeaderError(e.to_string())),
+        };
+
+        let client = Client::builder().default_headers(headers).build()?;
+
+        Ok(GitHubClient {
+            client,
+            api_url: api_url.trim_end_matches('/').to_string(),
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+        })
+    }
+
+    /// Build a client for the repo that `remote_url` points to.
+    pub fn from_remote(opts: &GitHubOpts, remote_url: &str) -> CromResult<Self> {
+        let token = match &opts.github_token {
+            Some(token) => token,
+            None => bail!(ErrorKind::GitHubError(s!(
+                "A token is required, set GITHUB_TOKEN or --github-token"
+            ))),
+        };
+
+        let (owner, repo) = match parse_remote_url(remote_url) {
+            Some(parts) => parts,
+            None => bail!(ErrorKind::UnknownGitRemotes(remote_url.to_string())),
+        };
+
+        GitHubClient::new(&opts.github_api, token, &owner, &repo)
+    }
+
+    pub async fn create_release(
+        &self,
+        version: &Version,
+        target: &str,
+        message: &str,
+        prerelease: bool,
+    ) -> CromResult<GitHubRelease> {
+        let url = format!(
+            "{}/repos/{}/{}/releases",
+            self.api_url, self.owner, self.repo
+        );
+        let body = CreateRelease {
+            tag_name: version.to_string(),
+            target_commitish: target.to_string(),
+            name: version.to_string(),
+            body: message.to_string(),
+            draft: false,
+            prerelease,
+        };
+
+        debug!("Creating release {:?} at {}", body, url);
+        let response = self.client.post(&url).json(&body).send().await?;
+        let response = check_response(response).await?;
+
+        Ok(response.json::<GitHubRelease>().await?)
+    }
+
+    pub async fn find_release(&self, version: &Version) -> CromResult<GitHubRelease> {
+        let url = format!(
+            "{}/repos/{}/{}/releases/tags/{}",
+            self.api_url, self.owner, self.repo, version
+        );
+
+        debug!("Looking up release at {}", url);
+        let response = self.client.get(&url).send().await?;
+        let response = check_response(response).await?;
+
+        Ok(response.json::<GitHubRelease>().await?)
+    }
+
+    pub async fn upload_asset(
+        &self,
+        release: &GitHubRelease,
+        name: &str,
+        path: &Path,
+    ) -> CromResult<GitHubAsset> {
+        // The upload url is a hypermedia template, e.g. `.../assets{?name,label}`
+        let url = match release.upload_url.split_once('{') {
+            Some((url, _)) => url,
+            None => &release.upload_url,
+        };
+
+        let body = tokio::fs::read(path).await?;
+        debug!("Uploading {:?} as {} to {}", path, name, url);
+
+        let response = self
+            .client
+            .post(url)
+            .query(&[("name", name)])
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(body)
+            .send()
+            .await?;
+        let response = check_response(response).await?;
+
+        Ok(response.json::<GitHubAsset>().await?)
+    }
+}
+
+async fn check_response(response: Response) -> CromResult<Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let url = response.url().to_string();
+    let text = response.text().await.unwrap_or_default();
+    bail!(ErrorKind::GitHubError(format!(
+        "{} returned {}: {}",
+        url, status, text
+    )))
+}
+
+/// Extract the owner and repo name from a git remote url.
+///
+/// Supports `git@host:owner/repo.git`, `ssh://git@host/owner/repo.git`
+/// and `https://host/owner/repo.git` style urls.
+pub fn parse_remote_url(url: &str) -> Option<(String, String)> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+
+    let path = path.trim_end_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let (owner, repo) = path.rsplit_once('/')?;
+    let owner = owner.rsplit('/').next()?;
+
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+
+    Some((owner.to_string(), repo.to_string()))
+}
+
+#[test]
+fn test_parse_remote_url() {
+    let expected = Some((s!("ethankhall"), s!("crom")));
+
+    assert_eq!(
+        expected,
+        parse_remote_url("git@github.com:ethankhall/crom.git")
+    );
+    assert_eq!(
+        expected,
+        parse_remote_url("https://github.com/ethankhall/crom.git")
+    );
+    assert_eq!(
+        expected,
+        parse_remote_url("https://github.com/ethankhall/crom")
+    );
+    assert_eq!(
+        expected,
+        parse_remote_url("ssh://git@github.com/ethankhall/crom.git")
+    );
+    assert_eq!(None, parse_remote_url("https://github.com/crom"));
+}
+
+#[tokio::test]
+async fn test_create_release() {
+    use mockito::{mock, Matcher};
+    use serde_json::json;
+
+    let matcher = crate::version::VersionMatcher::new("v0.1.%d");
+    let version = matcher.match_version(s!("v0.1.2")).unwrap();
+
+    let mock = mock("POST", "/repos/ethankhall/crom/releases")
+        .match_header("authorization", "token abc123")
+        .match_body(Matcher::PartialJson(json!({
+            "tag_name": "v0.1.2",
+            "target_commitish": "0cc81e3",
+            "body": "Created v0.1.2",
+            "prerelease": false,
+        })))
+        .with_status(201)
+        .with_body(
+            json!({
+                "id": 1,
+                "tag_name": "v0.1.2",
+                "html_url": "https://github.com/ethankhall/crom/releases/v0.1.2",
+                "upload_url": "https://uploads.github.com/repos/ethankhall/crom/releases/1/assets{?name,label}",
+            })
+            .to_string(),
+        )
+        .create();
+
+    let client = GitHubClient::new(&mockito::server_url(), "abc123", "ethankhall", "crom").unwrap();
+    let release = client
+        .create_release(&version, "0cc81e3", "Created v0.1.2", false)
+        .await
+        .unwrap();
+
+    mock.assert();
+    assert_eq!("v0.1.2", release.tag_name);
+    assert_eq!(
+        "https://github.com/ethankhall/crom/releases/v0.1.2",
+        release.html_url
+    );
+}
+
+#[tokio::test]
+async fn test_create_release_error() {
+    let _mock = mockito::mock("POST", "/repos/ethankhall/crom-error/releases")
+        .with_status(422)
+        .with_body("{\"message\": \"Validation Failed\"}")
+        .create();
+
+    let version = Version::from(s!("v0.1.2"));
+    let client =
+        GitHubClient::new(&mockito::server_url(), "abc123", "ethankhall", "crom-error").unwrap();
+    let err = client
+        .create_release(&version, "0cc81e3", "", false)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err.kind(), ErrorKind::GitHubError(_)));
+}
+
+#[tokio::test]
+async fn test_upload_asset() {
+    use mockito::{mock, Matcher};
+    use serde_json::json;
+    use std::io::Write;
+
+    let version = Version::from(s!("v0.1.3"));
+    let find_mock = mock("GET", "/repos/ethankhall/crom/releases/tags/v0.1.3")
+        .with_status(200)
+        .with_body(
+            json!({
+                "tag_name": "v0.1.3",
+                "html_url": "https://github.com/ethankhall/crom/releases/v0.1.3",
+                "upload_url": format!("{}/uploads/3/assets{{?name,label}}", mockito::server_url()),
+            })
+            .to_string(),
+        )
+        .create();
+
+    let upload_mock = mock("POST", "/uploads/3/assets")
+        .match_query(Matcher::UrlEncoded(s!("name"), s!("crom")))
+        .match_header("content-type", "application/octet-stream")
+        .match_body("binary")
+        .with_status(201)
+        .with_body(
+            json!({
+                "name": "crom",
+                "browser_download_url": "https://github.com/ethankhall/crom/releases/download/v0.1.3/crom",
+            })
+            .to_string(),
+        )
+        .create();
+
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(b"binary").unwrap();
+
+    let client = GitHubClient::new(&mockito::server_url(), "abc123", "ethankhall", "crom").unwrap();
+    let release = client.find_release(&version).await.unwrap();
+    let asset = client
+        .upload_asset(&release, "crom", file.path())
+        .await
+        .unwrap();
+
+    find_mock.assert();
+    upload_mock.assert();
+    assert_eq!("crom", asset.name);
+}
@@ -0,0 +1,234 @@
+use log::debug;
+use regex::{Captures, Regex};
+use std::fs;
+use std::path::Path;
+
+use crate::CromResult;
+
+static BUILD_SCRIPTS: &[&str] = &["build.gradle", "build.gradle.kts"];
+static SETTINGS_SCRIPTS: &[&str] = &["settings.gradle", "settings.gradle.kts"];
+
+/// Set `version=` in a `gradle.properties`, adding it when it's missing.
+/// Comments, blank lines and every other property are kept as they are.
+fn update_properties_text(text: &str, version: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut found = false;
+
+    for line in text.split_inclusive('\n') {
+        let content = line.trim_end_matches(&['\r', '\n'][..]);
+        let ending = &line[content.len()..];
+        let trimmed = content.trim_start();
+
+        let key_len = trimmed
+            .find(|c: char| c == '=' || c == ':' || c.is_whitespace())
+            .unwrap_or(trimmed.len());
+        let is_comment = trimmed.starts_with('#') || trimmed.starts_with('!');
+
+        if found || is_comment || &trimmed[..key_len] != "version" {
+            result.push_str(line);
+            continue;
+        }
+
+        // Everything up to the value: indent, key, separator and spacing.
+        let rest = &trimmed[key_len..];
+        let value_start = rest.len()
+            - rest
+                .trim_start()
+                .trim_start_matches(&['=', ':'][..])
+                .trim_start()
+                .len();
+        let prefix_len = content.len() - rest.len() + value_start;
+
+        result.push_str(&content[..prefix_len]);
+        result.push_str(version);
+        result.push_str(ending);
+        found = true;
+    }
+
+    if !found {
+        if !result.is_empty() && !result.ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str(&format!("version={}\n", version));
+    }
+
+    result
+}
+
+/// Replace the value of `version = "..."` assignments in a Groovy or Kotlin
+/// build script, keeping the quotes that were used.
+fn update_build_script_text(text: &str, version: &str) -> String {
+    let assignment =
+        Regex::new(r#"(?m)^(\s*(?:project\.)?version\s*=\s*)("[^"\n]*"|'[^'\n]*')"#).unwrap();
+
+    assignment
+        .replace_all(text, |captures: &Captures| {
+            let quote = &captures[2][..1];
+            format!("{}{}{}{}", &captures[1], quote, version, quote)
+        })
+        .to_string()
+}
+
+/// Directories of the projects `include`d by a settings script, relative to
+/// the root project. The Kotlin `include(...)` call can span several lines.
+fn included_projects(text: &str) -> Vec<String> {
+    let include = Regex::new(r"(?m)^[ \t]*include\b[ \t]*(?:\(([^)]*)\)|(.*)$)").unwrap();
+    let name = Regex::new(r#""([^"\n]+)"|'([^'\n]+)'"#).unwrap();
+
+    let mut projects = Vec::new();
+    for include in include.captures_iter(text) {
+        let arguments = include.get(1).or_else(|| include.get(2)).unwrap().as_str();
+        // Drop `//` comments between the arguments of a multi-line include.
+        let arguments: Vec<&str> = arguments
+            .lines()
+            .map(|line| line.split("//").next().unwrap_or_default())
+            .collect();
+
+        for project in name.captures_iter(&arguments.join("\n")) {
+            let project = project.get(1).or_else(|| project.get(2)).unwrap().as_str();
+            let path = project.trim_start_matches(':').replace(':', "/");
+            if !path.is_empty() && !projects.contains(&path) {
+                projects.push(path);
+            }
+        }
+    }
+
+    projects
+}
+
+/// Set the version in the `gradle.properties` of the build at `directory`.
+pub fn update_properties(directory: &Path, version: &str) -> CromResult<()> {
+    let path = directory.join(crate::statics::GRADLE_PROPERTIES);
+    let text = if path.exists() {
+        fs::read_to_string(&path)?
+    } else {
+        String::new()
+    };
+
+    fs::write(&path, update_properties_text(&text, version))?;
+    debug!("Updated {:?} to {}", path, version);
+    Ok(())
+}
+
+/// Set the version in the build scripts of the root project at `directory`,
+/// and of every project its settings script includes.
+pub fn update_build_scripts(directory: &Path, version: &str) -> CromResult<()> {
+    let mut projects = vec![directory.to_path_buf()];
+    for settings in SETTINGS_SCRIPTS {
+        let path = directory.join(settings);
+        if path.exists() {
+            let text = fs::read_to_string(&path)?;
+            projects.extend(
+                included_projects(&text)
+                    .iter()
+                    .map(|project| directory.join(project)),
+            );
+        }
+    }
+
+    for project in projects {
+        for script in BUILD_SCRIPTS {
+            let path = project.join(script);
+            if !path.exists() {
+                continue;
+            }
+
+            let text = fs::read_to_string(&path)?;
+            let updated = update_build_script_text(&text, version);
+            if updated != text {
+                fs::write(&path, updated)?;
+                debug!("Updated {:?} to {}", path, version);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_update_properties_text() {
+    let text = "# The version is set by crom\nversion = 1.0.0\norg.gradle.jvmargs=-Xmx2g\n";
+    assert_eq!(
+        "# The version is set by crom\nversion = 1.1.0\norg.gradle.jvmargs=-Xmx2g\n",
+        update_properties_text(text, "1.1.0")
+    );
+
+    assert_eq!(
+        "#version=0.0.1\r\nversion:2.0\r\n",
+        update_properties_text("#version=0.0.1\r\nversion:1.0\r\n", "2.0")
+    );
+    assert_eq!(
+        "group=io.ehdev\nversion=1.0.0\n",
+        update_properties_text("group=io.ehdev", "1.0.0")
+    );
+    assert_eq!("version=1.0.0\n", update_properties_text("", "1.0.0"));
+}
+
+#[test]
+fn test_update_build_script_text() {
+    let groovy = "plugins {\n    id 'java'\n}\n\nversion = '1.0.0'\ndef versionName = 'x'\n";
+    assert_eq!(
+        "plugins {\n    id 'java'\n}\n\nversion = '1.1.0'\ndef versionName = 'x'\n",
+        update_build_script_text(groovy, "1.1.0")
+    );
+
+    let kotlin = "allprojects {\n    project.version = \"1.0.0\"\n}\n";
+    assert_eq!(
+        "allprojects {\n    project.version = \"1.1.0\"\n}\n",
+        update_build_script_text(kotlin, "1.1.0")
+    );
+
+    let mismatched = "version = \"1.0.0'\n";
+    assert_eq!(mismatched, update_build_script_text(mismatched, "1.1.0"));
+}
+
+#[test]
+fn test_included_projects() {
+    let settings = "include(\n    \":app\",\n    // \":old\",\n    \":libs:core\"\n)\ninclude ':web', \"docs\"\ninclude(\"bad')\n";
+    assert_eq!(
+        vec![s!("app"), s!("libs/core"), s!("web"), s!("docs")],
+        included_projects(settings)
+    );
+}
+
+#[test]
+fn test_update_build_scripts() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join("settings.gradle.kts"),
+        "rootProject.name = \"crom\"\ninclude(\"server\", \":libs:core\")\n// include(\"old\")\ninclude(\n    \":app\",\n    \":lib\"\n)\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("build.gradle.kts"), "version = \"1.0\"\n").unwrap();
+    for project in &["app", "lib"] {
+        fs::create_dir_all(dir.path().join(project)).unwrap();
+        fs::write(
+            dir.path().join(project).join("build.gradle.kts"),
+            "version = \"1.0\"\n",
+        )
+        .unwrap();
+    }
+    fs::create_dir_all(dir.path().join("libs/core")).unwrap();
+    fs::write(
+        dir.path().join("libs/core/build.gradle"),
+        "version = '1.0'\n",
+    )
+    .unwrap();
+
+    update_build_scripts(dir.path(), "1.1").unwrap();
+
+    assert_eq!(
+        "version = \"1.1\"\n",
+        fs::read_to_string(dir.path().join("build.gradle.kts")).unwrap()
+    );
+    assert_eq!(
+        "version = '1.1'\n",
+        fs::read_to_string(dir.path().join("libs/core/build.gradle")).unwrap()
+    );
+    for project in &["app", "lib"] {
+        assert_eq!(
+            "version = \"1.1\"\n",
+            fs::read_to_string(dir.path().join(project).join("build.gradle.kts")).unwrap()
+        );
+    }
+}
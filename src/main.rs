@@ -9,12 +9,16 @@ macro_rules! s {
     };
 }
 
+mod archive;
 mod cli;
 mod commands;
 mod errors;
 mod git_repo;
+mod github;
+mod gradle;
 mod logging;
 mod models;
+mod pom;
 mod statics;
 mod version;
 
@@ -41,6 +45,12 @@ async fn main() {
         SubCommand::Init(args) => crate::commands::run_init(args).await,
         SubCommand::Get(args) => crate::commands::run_get(args).await,
         SubCommand::WriteVersion(args) => crate::commands::run_write(args).await,
+        SubCommand::Tag(args) => crate::commands::run_tag(args).await,
+        SubCommand::Release(args) => crate::commands::run_release(args).await,
+        SubCommand::Upload(args) => crate::commands::run_upload(args).await,
+        SubCommand::Artifact(args) => crate::commands::run_artifact(args).await,
+        SubCommand::Changed(args) => crate::commands::run_changed(args).await,
+        SubCommand::Env(args) => crate::commands::run_env(args).await,
         SubCommand::Utility(args) => crate::commands::run_utils(args).await,
         #[cfg(feature = "gh-cli")]
         SubCommand::GitHub(gh) => run_gh(gh),
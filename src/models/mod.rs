@@ -27,8 +27,12 @@ pub async fn find_project_config() -> Result<(PathBuf, CromConfig), CromError> {
 fn parse_config(path: PathBuf) -> Result<CromConfig, CromError> {
     let contents = read_to_string(&path)?;
 
-    match toml::from_str::<CromConfig>(&contents) {
-        Ok(config) => Ok(config),
+    let config = match toml::from_str::<CromConfig>(&contents) {
+        Ok(config) => config,
         Err(e) => bail!(ErrorKind::ConfigInvalid(e.to_string())),
-    }
+    };
+
+    config.validate()?;
+
+    Ok(config)
 }
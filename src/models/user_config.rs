@@ -1,7 +1,10 @@
+use glob::Pattern;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use crate::version::VersionMatcher;
+use crate::errors::{Error as CromError, ErrorKind};
+use crate::version::{PreReleaseTemplate, Version, VersionMatcher};
 
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "kebab-case")]
@@ -11,20 +14,55 @@ pub struct CromConfig {
 
     #[serde(default)]
     pub artifact: HashMap<String, ProjectArtifacts>,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub component: HashMap<String, ComponentConfig>,
+}
+
+/// A `[component.<name>]` section, versioned independently of the rest of the repo.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct ComponentConfig {
+    /// Directory of the component, relative to `.crom.toml`.
+    pub directory: Option<String>,
+
+    #[serde(flatten)]
+    pub project: ProjectConfig,
+
+    #[serde(default)]
+    pub artifact: HashMap<String, ProjectArtifacts>,
+}
+
+/// One version stream in the repo, either the top level config or a component.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Component {
+    pub name: Option<String>,
+    pub directory: Option<String>,
+    pub project: ProjectConfig,
+    pub artifact: HashMap<String, ProjectArtifacts>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct ProjectConfig {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub pattern: String,
     pub cargo: Option<CargoConfig>,
     pub property: Option<PropertyFileConfig>,
     pub maven: Option<MavenConfig>,
+    pub gradle: Option<GradleConfig>,
     #[serde(rename = "node")]
     pub package_json: Option<NodeConfig>,
     #[serde(rename = "python")]
     pub version_py: Option<VersionPyConfig>,
+    pub pyproject: Option<PyProjectConfig>,
     pub message_template: Option<String>,
+    pub pre_release_template: Option<String>,
+    pub tag_scope: Option<TagScope>,
+    #[serde(default, rename = "branch", skip_serializing_if = "Vec::is_empty")]
+    pub branches: Vec<BranchConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
 }
 
 impl CromConfig {
@@ -32,27 +70,241 @@ impl CromConfig {
         let project_config = ProjectConfig {
             pattern,
             message_template: Some(message_template),
+            pre_release_template: None,
+            tag_scope: Some(TagScope::Reachable),
+            branches: Vec::new(),
+            paths: Vec::new(),
             cargo: None,
             property: None,
             maven: None,
+            gradle: None,
             package_json: None,
             version_py: None,
+            pyproject: None,
         };
 
         CromConfig {
             project: project_config,
             artifact: HashMap::new(),
+            component: HashMap::new(),
+        }
+    }
+
+    /// The components to work on.
+    ///
+    /// When `name` is given only that component is returned, otherwise
+    /// every component sorted by name. The top level config is included
+    /// when it has a `pattern`.
+    pub fn components(&self, name: Option<&str>) -> Result<Vec<Component>, CromError> {
+        if let Some(name) = name {
+            return match self.component.get(name) {
+                Some(component) => Ok(vec![component.to_component(name)]),
+                None => Err(
+                    ErrorKind::ConfigInvalid(format!("component {} is not defined", name)).into(),
+                ),
+            };
+        }
+
+        let mut components = Vec::new();
+        if !self.project.pattern.is_empty() {
+            components.push(Component {
+                name: None,
+                directory: None,
+                project: self.project.clone(),
+                artifact: self.artifact.clone(),
+            });
+        }
+
+        let mut names: Vec<&String> = self.component.keys().collect();
+        names.sort();
+        for name in names {
+            components.push(self.component[name].to_component(name));
+        }
+
+        Ok(components)
+    }
+
+    /// Make sure every component has a valid pattern and templates.
+    pub fn validate(&self) -> Result<(), CromError> {
+        if self.component.is_empty() && self.project.pattern.is_empty() {
+            return Err(ErrorKind::ConfigInvalid(s!("missing field `pattern`")).into());
+        }
+
+        for component in self.components(None)? {
+            if component.project.pattern.is_empty() {
+                return Err(ErrorKind::ConfigInvalid(format!(
+                    "component {} is missing `pattern`",
+                    component.name.unwrap_or_default()
+                ))
+                .into());
+            }
+
+            component.project.create_pre_release_template()?;
+            component.create_path_patterns("")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ComponentConfig {
+    fn to_component(&self, name: &str) -> Component {
+        Component {
+            name: Some(name.to_string()),
+            directory: self.directory.clone(),
+            project: self.project.clone(),
+            artifact: self.artifact.clone(),
+        }
+    }
+}
+
+impl Component {
+    /// Names from `names` that this component defines an artifact for.
+    pub fn artifact_names(&self, names: &[String]) -> Vec<String> {
+        names
+            .iter()
+            .filter(|name| self.artifact.contains_key(*name))
+            .cloned()
+            .collect()
+    }
+
+    /// Globs for the files that belong to this component, relative to the repo.
+    ///
+    /// `prefix` is the component's directory inside the repo. Without any
+    /// `paths`, everything under `prefix` is used.
+    pub fn create_path_patterns(&self, prefix: &str) -> Result<Vec<Pattern>, CromError> {
+        let prefix = prefix.trim_end_matches('/');
+        let globs = if self.project.paths.is_empty() {
+            vec![s!("**")]
+        } else {
+            self.project.paths.clone()
+        };
+
+        let mut patterns = Vec::new();
+        for glob in globs {
+            let glob = if prefix.is_empty() {
+                glob
+            } else {
+                format!("{}/{}", prefix, glob)
+            };
+
+            match Pattern::new(&glob) {
+                Ok(pattern) => patterns.push(pattern),
+                Err(e) => {
+                    return Err(ErrorKind::ConfigInvalid(format!("paths {} {}", glob, e)).into())
+                }
+            }
+        }
+
+        Ok(patterns)
+    }
+
+    /// Directory the component's files are relative to.
+    pub fn root(&self, location: &Path) -> PathBuf {
+        match &self.directory {
+            Some(directory) => location.join(directory),
+            None => location.to_path_buf(),
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Pattern to use on `branch`.
+    ///
+    /// The first `[[branch]]` rule whose name matches is used, falling back
+    /// to the top level `pattern`.
+    pub fn find_pattern(&self, branch: &str) -> String {
+        match self.branches.iter().find(|rule| rule.matches(branch)) {
+            Some(rule) => {
+                let suffix = branch.rsplit('/').next().unwrap_or(branch);
+                rule.pattern.replace("{branch.suffix}", suffix)
+            }
+            None => self.pattern.clone(),
         }
     }
 
-    pub fn create_version_matcher(&self) -> VersionMatcher {
-        VersionMatcher::new(&self.project.pattern)
+    pub fn create_version_matcher(&self, branch: &str) -> VersionMatcher {
+        VersionMatcher::new(&self.find_pattern(branch))
+    }
+
+    pub fn create_tag_message(&self, version: &Version) -> String {
+        let template = match &self.message_template {
+            Some(template) => template.as_str(),
+            None => crate::statics::DEFAULT_MESSAGE_TEMPLATE,
+        };
+
+        template.replace("{version}", &version.to_string())
+    }
+
+    pub fn tag_scope(&self) -> TagScope {
+        self.tag_scope.clone().unwrap_or(TagScope::All)
+    }
+
+    pub fn create_pre_release_template(&self) -> Result<PreReleaseTemplate, CromError> {
+        match &self.pre_release_template {
+            None => Ok(PreReleaseTemplate::default()),
+            Some(template) => PreReleaseTemplate::parse(template),
+        }
+    }
+}
+
+/// A `[[branch]]` rule, using `pattern` on branches matching `name`.
+///
+/// `name` can use `*` to match any text, e.g. `release/*`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct BranchConfig {
+    pub name: String,
+    pub pattern: String,
+}
+
+impl BranchConfig {
+    fn matches(&self, branch: &str) -> bool {
+        let mut parts = self.name.split('*');
+        let first = parts.next().unwrap_or_default();
+        let mut rest = match branch.strip_prefix(first) {
+            Some(rest) => rest,
+            None => return false,
+        };
+
+        let parts: Vec<&str> = parts.collect();
+        let (last, middle) = match parts.split_last() {
+            Some(split) => split,
+            None => return rest.is_empty(),
+        };
+
+        for part in middle {
+            match rest.find(part) {
+                Some(i) => rest = &rest[i + part.len()..],
+                None => return false,
+            }
+        }
+
+        rest.ends_with(last)
     }
 }
 
+/// Which tags are used to find the latest version.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum TagScope {
+    /// Every tag in the repo.
+    All,
+    /// Only tags on commits that HEAD contains.
+    Reachable,
+}
+
 #[derive(Serialize, Debug, PartialEq, Clone, Deserialize)]
 pub struct VersionPyConfig {
     pub path: String,
+    #[serde(default = "default_version_variable")]
+    pub variable: String,
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone, Deserialize)]
+pub struct PyProjectConfig {
+    #[serde(default = "default_none_path")]
+    #[serde(alias = "path")]
+    pub directory: Option<String>,
 }
 
 #[derive(Serialize, Debug, PartialEq, Clone, Deserialize)]
@@ -70,7 +322,69 @@ pub struct CargoConfig {
 }
 
 #[derive(Serialize, Debug, PartialEq, Clone, Deserialize)]
-pub struct MavenConfig {}
+pub struct MavenConfig {
+    #[serde(default = "default_none_path")]
+    pub directory: Option<String>,
+    #[serde(default = "default_pom_path")]
+    pub path: String,
+    #[serde(default)]
+    pub mode: MavenMode,
+    #[serde(default = "default_maven_executable")]
+    pub executable: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub profiles: Vec<String>,
+}
+
+impl MavenConfig {
+    /// Arguments for running `versions:set` in `mvn` mode.
+    pub fn versions_set_args(&self, version: &str) -> Vec<String> {
+        let mut args = vec![
+            s!("-f"),
+            self.path.clone(),
+            s!("versions:set"),
+            format!("-DnewVersion={}", version),
+            s!("-DprocessAllModules=true"),
+            s!("-DgenerateBackupPoms=false"),
+        ];
+
+        if !self.profiles.is_empty() {
+            args.push(format!("-P{}", self.profiles.join(",")));
+        }
+
+        args.extend(self.args.iter().cloned());
+        args
+    }
+}
+
+/// How the version in `pom.xml` is updated. Defaults to `mvn`, which is
+/// what configs written before `mode` existed expect.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum MavenMode {
+    /// Edit the `pom.xml` files directly.
+    Native,
+    /// Run `mvn versions:set`.
+    Mvn,
+}
+
+impl Default for MavenMode {
+    fn default() -> Self {
+        MavenMode::Mvn
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct GradleConfig {
+    #[serde(default = "default_none_path")]
+    #[serde(alias = "path")]
+    pub directory: Option<String>,
+    /// Also update `version = "..."` in the build scripts.
+    #[serde(default)]
+    pub build_script: bool,
+}
 
 #[derive(Serialize, Debug, PartialEq, Clone, Deserialize)]
 pub struct PropertyFileConfig {
@@ -86,6 +400,18 @@ fn default_propery_file_path() -> String {
     s!(crate::statics::VERSION_PROPERTIES)
 }
 
+fn default_version_variable() -> String {
+    s!("__version__")
+}
+
+fn default_pom_path() -> String {
+    s!(crate::statics::POM_XML)
+}
+
+fn default_maven_executable() -> String {
+    s!("mvn")
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum ProjectArtifactCompressionFormat {
     #[serde(alias = "zip", alias = "ZIP")]
@@ -122,9 +448,12 @@ message-template = \"Created {version} for release.\"
 
 [cargo]
 [maven]
+[gradle]
+build-script = true
 [node]
 [python]
 path = \"path/to/version.py\"
+[pyproject]
 [property]
 path = \"path/to/property-file.properties\"
 ";
@@ -132,7 +461,24 @@ path = \"path/to/property-file.properties\"
     let config = toml::from_str::<CromConfig>(example_text).unwrap();
     println!("config: {:?}", config);
     assert_eq!(Some(CargoConfig { directory: None }), config.project.cargo);
-    assert_eq!(Some(MavenConfig {}), config.project.maven);
+    assert_eq!(
+        Some(MavenConfig {
+            directory: None,
+            path: s!("pom.xml"),
+            mode: MavenMode::Mvn,
+            executable: s!("mvn"),
+            args: vec![],
+            profiles: vec![],
+        }),
+        config.project.maven
+    );
+    assert_eq!(
+        Some(GradleConfig {
+            directory: None,
+            build_script: true,
+        }),
+        config.project.gradle
+    );
     assert_eq!(
         Some(NodeConfig { directory: None }),
         config.project.package_json
@@ -140,9 +486,14 @@ path = \"path/to/property-file.properties\"
     assert_eq!(
         Some(VersionPyConfig {
             path: s!("path/to/version.py"),
+            variable: s!("__version__"),
         }),
         config.project.version_py
     );
+    assert_eq!(
+        Some(PyProjectConfig { directory: None }),
+        config.project.pyproject
+    );
     assert_eq!(
         Some(PropertyFileConfig {
             path: s!("path/to/property-file.properties"),
@@ -150,3 +501,210 @@ path = \"path/to/property-file.properties\"
         config.project.property
     );
 }
+
+#[test]
+fn verify_maven_mode() {
+    let example_text = "
+pattern = 'v0.1.%d'
+
+[maven]
+directory = 'server'
+executable = './mvnw'
+args = ['-B', '-q']
+profiles = ['release', 'ci']
+";
+
+    let config = toml::from_str::<CromConfig>(example_text).unwrap();
+    let maven = config.project.maven.unwrap();
+    assert_eq!(Some(s!("server")), maven.directory);
+    assert_eq!(MavenMode::Mvn, maven.mode);
+    assert_eq!("./mvnw", maven.executable);
+    assert_eq!(
+        vec![
+            "-f",
+            "pom.xml",
+            "versions:set",
+            "-DnewVersion=1.2.3",
+            "-DprocessAllModules=true",
+            "-DgenerateBackupPoms=false",
+            "-Prelease,ci",
+            "-B",
+            "-q"
+        ],
+        maven.versions_set_args("1.2.3")
+    );
+    let config =
+        toml::from_str::<CromConfig>("pattern = 'v0.1.%d'\n[maven]\nmode = 'native'").unwrap();
+    assert_eq!(MavenMode::Native, config.project.maven.unwrap().mode);
+}
+
+#[test]
+fn verify_tag_message() {
+    let mut config = CromConfig::create_default(s!("v0.1.%d"), s!("Release {version}!"));
+    let version = Version::from(s!("v0.1.4"));
+
+    assert_eq!(
+        "Release v0.1.4!",
+        config.project.create_tag_message(&version)
+    );
+
+    config.project.message_template = None;
+    assert_eq!(
+        "Created v0.1.4 for release -- Crom",
+        config.project.create_tag_message(&version)
+    );
+}
+
+#[test]
+fn verify_pre_release_template() {
+    let mut config = CromConfig::create_default(s!("v0.1.%d"), s!("Release {version}!"));
+    assert_eq!(
+        PreReleaseTemplate::default(),
+        config.project.create_pre_release_template().unwrap()
+    );
+
+    config.project.pre_release_template = Some(s!("{next}-{sha:12}"));
+    assert!(config.project.create_pre_release_template().is_ok());
+
+    config.project.pre_release_template = Some(s!("{next}-{unknown}"));
+    let err = config.project.create_pre_release_template().unwrap_err();
+    assert!(matches!(
+        err.kind(),
+        ErrorKind::InvalidPreReleaseTemplate(_)
+    ));
+}
+
+#[test]
+fn verify_tag_scope() {
+    let config = toml::from_str::<CromConfig>("pattern = 'v0.1.%d'").unwrap();
+    assert_eq!(TagScope::All, config.project.tag_scope());
+
+    let config =
+        toml::from_str::<CromConfig>("pattern = 'v0.1.%d'\ntag-scope = \"reachable\"").unwrap();
+    assert_eq!(TagScope::Reachable, config.project.tag_scope());
+
+    let config = CromConfig::create_default(s!("v0.1.%d"), s!("Release {version}!"));
+    let text = toml::to_string_pretty(&config).unwrap();
+    let config = toml::from_str::<CromConfig>(&text).unwrap();
+    assert_eq!(Some(TagScope::Reachable), config.project.tag_scope);
+}
+
+#[test]
+fn verify_branch_patterns() {
+    let example_text = "
+pattern = 'v0.%d'
+
+[[branch]]
+name = 'main'
+pattern = 'v1.%d'
+
+[[branch]]
+name = 'release/*'
+pattern = 'v1.{branch.suffix}.%d'
+";
+
+    let config = toml::from_str::<CromConfig>(example_text).unwrap();
+    assert_eq!("v1.%d", config.project.find_pattern("main"));
+    assert_eq!("v1.4.%d", config.project.find_pattern("release/4"));
+    assert_eq!("v0.%d", config.project.find_pattern("feature/foo"));
+    assert_eq!("v0.%d", config.project.find_pattern("HEAD"));
+    assert_eq!("v0.%d", config.project.find_pattern("main-old"));
+
+    let matcher = config.project.create_version_matcher("release/4");
+    assert!(matcher.match_version(s!("v1.4.2")).is_some());
+    assert!(matcher.match_version(s!("v1.5.2")).is_none());
+}
+
+#[test]
+fn verify_branch_name_glob() {
+    let rule = |name: &str| BranchConfig {
+        name: name.to_string(),
+        pattern: s!("%d"),
+    };
+
+    assert!(rule("*").matches("anything/at-all"));
+    assert!(rule("release/*").matches("release/1.4"));
+    assert!(!rule("release/*").matches("hotfix/1.4"));
+    assert!(rule("*/hotfix-*").matches("team/hotfix-12"));
+    assert!(!rule("*/hotfix-*").matches("team/feature-12"));
+    assert!(rule("main").matches("main"));
+    assert!(!rule("main").matches("main2"));
+}
+
+#[test]
+fn verify_components() {
+    let example_text = "
+[component.server]
+pattern = 'server-v1.%d'
+directory = 'server'
+[component.server.cargo]
+
+[component.ui]
+pattern = 'ui-v2.%d'
+directory = 'ui'
+[component.ui.node]
+[component.ui.artifact.dist]
+paths = { 'ui.tgz' = 'dist/ui.tgz' }
+target = 'GitHub'
+";
+
+    let config = toml::from_str::<CromConfig>(example_text).unwrap();
+    config.validate().unwrap();
+
+    let components = config.components(None).unwrap();
+    assert_eq!(2, components.len());
+    assert_eq!(Some(s!("server")), components[0].name);
+    assert_eq!("server-v1.%d", components[0].project.pattern);
+    assert_eq!(
+        Some(CargoConfig { directory: None }),
+        components[0].project.cargo
+    );
+    assert_eq!(Some(s!("ui")), components[1].name);
+    assert!(components[1].artifact.contains_key("dist"));
+    assert_eq!(
+        PathBuf::from("/repo/ui"),
+        components[1].root(Path::new("/repo"))
+    );
+
+    let components = config.components(Some("ui")).unwrap();
+    assert_eq!(1, components.len());
+    assert_eq!("ui-v2.%d", components[0].project.pattern);
+
+    let err = config.components(Some("docs")).unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::ConfigInvalid(_)));
+}
+
+#[test]
+fn verify_component_validation() {
+    let config = toml::from_str::<CromConfig>("message-template = 'foo'").unwrap();
+    assert!(config.validate().is_err());
+
+    let config = toml::from_str::<CromConfig>("[component.server]\ndirectory = 'server'").unwrap();
+    assert!(config.validate().is_err());
+
+    let config = toml::from_str::<CromConfig>("pattern = 'v%d'").unwrap();
+    config.validate().unwrap();
+    assert_eq!(1, config.components(None).unwrap().len());
+}
+
+#[test]
+fn verify_path_patterns() {
+    let mut config = CromConfig::create_default(s!("v0.1.%d"), s!("Release {version}!"));
+    let component = &config.components(None).unwrap()[0];
+    let patterns = component.create_path_patterns("").unwrap();
+    assert!(patterns[0].matches("src/main.rs"));
+
+    let patterns = component.create_path_patterns("server/").unwrap();
+    assert!(patterns[0].matches("server/src/main.rs"));
+    assert!(!patterns[0].matches("ui/package.json"));
+
+    config.project.paths = vec![s!("src/**"), s!("Cargo.toml")];
+    let component = &config.components(None).unwrap()[0];
+    let patterns = component.create_path_patterns("server").unwrap();
+    assert!(patterns.iter().any(|p| p.matches("server/src/lib.rs")));
+    assert!(patterns.iter().any(|p| p.matches("server/Cargo.toml")));
+    assert!(!patterns.iter().any(|p| p.matches("server/README.md")));
+
+    config.project.paths = vec![s!("src/[")];
+    assert!(config.validate().is_err());
+}
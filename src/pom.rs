@@ -0,0 +1,383 @@
+use error_chain::bail;
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::errors::ErrorKind;
+use crate::CromResult;
+
+/// An element in a `pom.xml`, with the byte range of its content.
+#[derive(Debug, Clone)]
+struct Element {
+    path: Vec<String>,
+    start: usize,
+    end: usize,
+}
+
+/// Just enough of an XML reader to find elements in a `pom.xml`, so their
+/// text can be replaced without touching the rest of the file.
+struct Pom {
+    text: String,
+    elements: Vec<Element>,
+}
+
+impl Pom {
+    fn parse(text: String) -> Result<Self, String> {
+        let mut elements = Vec::new();
+        let mut stack: Vec<(String, usize)> = Vec::new();
+        let mut position = 0;
+
+        while let Some(offset) = text[position..].find('<') {
+            let start = position + offset;
+            let rest = &text[start..];
+
+            let skip_to = |end: &str| match rest.find(end) {
+                Some(i) => Ok(start + i + end.len()),
+                None => Err(format!("unclosed {} at {}", &rest[..2], start)),
+            };
+
+            position = if rest.starts_with("<!--") {
+                skip_to("-->")?
+            } else if rest.starts_with("<![CDATA[") {
+                skip_to("]]>")?
+            } else if rest.starts_with("<?") {
+                skip_to("?>")?
+            } else if rest.starts_with("<!") {
+                skip_to(">")?
+            } else {
+                let end = start + find_tag_end(rest).ok_or(format!("unclosed tag at {}", start))?;
+                let tag = &text[start + 1..end];
+
+                if let Some(name) = tag.strip_prefix('/') {
+                    let name = name.trim();
+                    match stack.pop() {
+                        Some((open, content_start)) if open == name => {
+                            let mut path: Vec<String> =
+                                stack.iter().map(|(name, _)| name.clone()).collect();
+                            path.push(open);
+                            elements.push(Element {
+                                path,
+                                start: content_start,
+                                end: start,
+                            });
+                        }
+                        _ => return Err(format!("unexpected </{}> at {}", name, start)),
+                    }
+                } else if !tag.ends_with('/') {
+                    let name = tag.split_whitespace().next().unwrap_or_default();
+                    stack.push((name.to_string(), end + 1));
+                }
+
+                end + 1
+            };
+        }
+
+        if let Some((name, _)) = stack.pop() {
+            return Err(format!("<{}> is never closed", name));
+        }
+
+        Ok(Pom { text, elements })
+    }
+
+    fn find(&self, path: &[&str]) -> Option<&Element> {
+        self.elements.iter().find(|element| element.path == path)
+    }
+
+    fn value(&self, path: &[&str]) -> Option<&str> {
+        self.find(path)
+            .map(|element| self.text[element.start..element.end].trim())
+    }
+
+    fn modules(&self) -> Vec<String> {
+        self.elements
+            .iter()
+            .filter(|element| element.path == ["project", "modules", "module"])
+            .map(|element| self.text[element.start..element.end].trim().to_string())
+            .collect()
+    }
+}
+
+/// Index of the `>` that ends the tag at the start of `text`, skipping
+/// over quoted attribute values.
+fn find_tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Name of the property when `value` is exactly `${name}`.
+fn property_name(value: &str) -> Option<&str> {
+    let name = value.strip_prefix("${")?.strip_suffix('}')?;
+    if name.contains('$') {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Rewrite the versions in one `pom.xml`.
+///
+/// `old_version` is the version of the root project before the update, and
+/// `reactor` the artifactIds of the projects this one is a module of.
+fn update_pom_text(
+    pom: &Pom,
+    version: &str,
+    old_version: Option<&str>,
+    reactor: &[String],
+) -> String {
+    let mut edits: Vec<(usize, usize)> = Vec::new();
+
+    let mut set = |path: &[&str]| {
+        let value = match pom.value(path) {
+            Some(value) => value,
+            None => return,
+        };
+
+        let path: Vec<&str> = match property_name(value) {
+            Some(name) => vec!["project", "properties", name],
+            None => path.to_vec(),
+        };
+
+        if let Some(element) = pom.find(&path) {
+            if !edits.contains(&(element.start, element.end)) {
+                edits.push((element.start, element.end));
+            }
+        }
+    };
+
+    let is_root = reactor.is_empty();
+    let project_version = pom.value(&["project", "version"]);
+    if is_root || (project_version.is_some() && project_version == old_version) {
+        set(&["project", "version"]);
+    }
+
+    let parent = pom.value(&["project", "parent", "artifactId"]);
+    if !is_root && reactor.iter().any(|id| Some(id.as_str()) == parent) {
+        set(&["project", "parent", "version"]);
+    }
+
+    edits.sort();
+    let mut text = pom.text.clone();
+    for (start, end) in edits.into_iter().rev() {
+        text.replace_range(start..end, version);
+    }
+
+    text
+}
+
+fn read_pom(pom_path: &Path) -> CromResult<Pom> {
+    if !pom_path.exists() {
+        bail!(ErrorKind::FileNotFound(pom_path.to_path_buf()));
+    }
+
+    match Pom::parse(fs::read_to_string(pom_path)?) {
+        Ok(pom) => Ok(pom),
+        Err(e) => bail!(ErrorKind::InvalidXml(format!("{:?}: {}", pom_path, e))),
+    }
+}
+
+/// Read the modules of `pom`, and theirs, adding each with the artifactIds
+/// of the projects it is a module of.
+fn read_modules(
+    pom_path: &Path,
+    pom: &Pom,
+    reactor: &[String],
+    poms: &mut Vec<(PathBuf, Pom, Vec<String>)>,
+) -> CromResult<()> {
+    let mut reactor = reactor.to_vec();
+    if let Some(artifact_id) = pom.value(&["project", "artifactId"]) {
+        reactor.push(artifact_id.to_string());
+    }
+
+    let directory = pom_path.parent().unwrap_or_else(|| Path::new("."));
+    for module in pom.modules() {
+        let mut module_path = directory.join(&module);
+        if !module.ends_with(".xml") {
+            module_path.push(crate::statics::POM_XML);
+        }
+
+        let module_pom = read_pom(&module_path)?;
+        read_modules(&module_path, &module_pom, &reactor, poms)?;
+        poms.push((module_path, module_pom, reactor.clone()));
+    }
+
+    Ok(())
+}
+
+/// Set the version of the project at `pom_path`, and of every module it
+/// includes, by editing the files in place.
+///
+/// Every file is read before any is written, so a missing or invalid module
+/// leaves all of them unchanged.
+pub fn update_versions(pom_path: &Path, version: &str) -> CromResult<()> {
+    let pom = read_pom(pom_path)?;
+    let old_version = pom
+        .value(&["project", "version"])
+        .and_then(|value| match property_name(value) {
+            Some(name) => pom.value(&["project", "properties", name]),
+            None => Some(value),
+        })
+        .map(|value| value.to_string());
+
+    let mut poms = Vec::new();
+    read_modules(pom_path, &pom, &[], &mut poms)?;
+    poms.insert(0, (pom_path.to_path_buf(), pom, Vec::new()));
+
+    for (path, pom, reactor) in poms {
+        fs::write(
+            &path,
+            update_pom_text(&pom, version, old_version.as_deref(), &reactor),
+        )?;
+        debug!("Updated {:?} to {}", path, version);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+fn write_test_pom(dir: &Path, name: &str, contents: &str) -> std::path::PathBuf {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn test_update_versions() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = write_test_pom(
+        dir.path(),
+        "pom.xml",
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+    <!-- <version>not this one</version> -->
+    <parent>
+        <groupId>org.springframework.boot</groupId>
+        <artifactId>spring-boot-starter-parent</artifactId>
+        <version>2.7.0</version>
+    </parent>
+    <artifactId>crom-parent</artifactId>
+    <version>1.0.0</version>
+    <packaging>pom</packaging>
+    <modules>
+        <module>server</module>
+    </modules>
+    <dependencies>
+        <dependency>
+            <groupId>junit</groupId>
+            <artifactId>junit</artifactId>
+            <version>4.13</version>
+        </dependency>
+    </dependencies>
+</project>
+"#,
+    );
+    let server = write_test_pom(
+        dir.path(),
+        "server/pom.xml",
+        r#"<project>
+  <parent>
+    <artifactId>crom-parent</artifactId>
+    <version>1.0.0</version>
+  </parent>
+  <artifactId>server</artifactId>
+  <version>1.0.0</version>
+</project>
+"#,
+    );
+
+    update_versions(&root, "1.1.0").unwrap();
+
+    let text = fs::read_to_string(&root).unwrap();
+    assert!(text.contains("<version>not this one</version>"));
+    assert!(text.contains("<version>2.7.0</version>"));
+    assert!(
+        text.contains("    <artifactId>crom-parent</artifactId>\n    <version>1.1.0</version>\n")
+    );
+    assert!(text.contains("<version>4.13</version>"));
+
+    assert_eq!(
+        r#"<project>
+  <parent>
+    <artifactId>crom-parent</artifactId>
+    <version>1.1.0</version>
+  </parent>
+  <artifactId>server</artifactId>
+  <version>1.1.0</version>
+</project>
+"#,
+        fs::read_to_string(&server).unwrap()
+    );
+}
+
+#[test]
+fn test_update_revision_property() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = write_test_pom(
+        dir.path(),
+        "pom.xml",
+        r#"<project>
+  <artifactId>crom-parent</artifactId>
+  <version>${revision}</version>
+  <properties>
+    <revision>1.0.0-SNAPSHOT</revision>
+  </properties>
+  <modules>
+    <module>ui/pom.xml</module>
+  </modules>
+</project>
+"#,
+    );
+    let ui = write_test_pom(
+        dir.path(),
+        "ui/pom.xml",
+        r#"<project>
+  <parent>
+    <artifactId>crom-parent</artifactId>
+    <version>${revision}</version>
+  </parent>
+  <artifactId>ui</artifactId>
+</project>
+"#,
+    );
+    let ui_before = fs::read_to_string(&ui).unwrap();
+
+    update_versions(&root, "1.0.1").unwrap();
+
+    let text = fs::read_to_string(&root).unwrap();
+    assert!(text.contains("<version>${revision}</version>"));
+    assert!(text.contains("<revision>1.0.1</revision>"));
+    assert_eq!(ui_before, fs::read_to_string(&ui).unwrap());
+}
+
+#[test]
+fn test_invalid_pom() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = write_test_pom(dir.path(), "pom.xml", "<project><version>1</project>");
+
+    let err = update_versions(&root, "2").unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidXml(_)));
+
+    let text = "<project><version>1</version><modules><module>missing</module></modules></project>";
+    let root = write_test_pom(dir.path(), "pom.xml", text);
+    let err = update_versions(&root, "2").unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::FileNotFound(_)));
+    assert_eq!(text, fs::read_to_string(&root).unwrap());
+
+    write_test_pom(
+        dir.path(),
+        "missing/pom.xml",
+        "<project><version>1</project>",
+    );
+    let err = update_versions(&root, "2").unwrap_err();
+    assert!(matches!(err.kind(), ErrorKind::InvalidXml(_)));
+    assert_eq!(text, fs::read_to_string(&root).unwrap());
+}
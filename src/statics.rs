@@ -1,5 +1,14 @@
 pub static CONFIG_FILE: &str = ".crom.toml";
+pub static DEFAULT_MESSAGE_TEMPLATE: &str = "Created {version} for release -- Crom";
+pub static DEFAULT_PRE_RELEASE_TEMPLATE: &str = "{next}-{sha}";
 
 pub static PACKAGE_JSON: &str = "package.json";
 pub static VERSION_PROPERTIES: &str = "version.properties";
 pub static CARGO_TOML: &str = "Cargo.toml";
+pub static PYPROJECT_TOML: &str = "pyproject.toml";
+pub static POM_XML: &str = "pom.xml";
+pub static GRADLE_PROPERTIES: &str = "gradle.properties";
+
+pub static GITLAB_DOTENV_FILE: &str = "crom.env";
+pub static BRANCH_ENV_VAR: &str = "CROM_BRANCH";
+pub static GIT_TOKEN_ENV_VARS: &[&str] = &["CROM_GIT_TOKEN", "GITHUB_TOKEN"];
@@ -2,6 +2,19 @@
 pub enum VersionComponent {
     Static(String),
     Changing(i32),
+    /// Part of the date the version was created on.
+    Date(DatePart, i32),
+    /// A counter that starts over at 0 whenever a `Date` part changes.
+    Period(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatePart {
+    Year,
+    Month,
+    /// ISO 8601 week, when used `Year` is the ISO week-numbering year.
+    Week,
+    Day,
 }
 
 #[derive(Debug, Clone, Eq)]
@@ -9,12 +22,56 @@ pub struct Version {
     parts: Vec<VersionComponent>,
     is_only_static: bool,
     pre_release: Option<String>,
+    build: Option<String>,
+}
+
+/// Which counter to increment when computing the next version.
+#[derive(clap::ArgEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionBump {
+    Major,
+    Minor,
+    Patch,
 }
 
 #[derive(Debug)]
 pub struct VersionMatcher {
     pattern: Vec<VersionComponent>,
+    regex: regex::Regex,
+}
+
+/// Template used to build pre-release versions, like `{next}-{sha}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreReleaseTemplate {
+    tokens: Vec<TemplateToken>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplateToken {
+    Literal(String),
+    Sha(usize),
+    Branch,
+    Timestamp,
+    Distance,
+}
+
+/// Values from the repo that can be used in a `PreReleaseTemplate`.
+#[derive(Debug, Clone)]
+pub struct PreReleaseValues {
+    /// Full sha of HEAD
+    pub sha: String,
+    /// Current branch name
+    pub branch: String,
+    /// Commit time of HEAD, in seconds since the epoch
+    pub timestamp: i64,
+    /// Number of commits since the latest tag HEAD contains
+    pub distance: usize,
+}
+
+mod pre_release;
 mod version_impl;
 mod version_parser;
+
+/// The local date, used for the date parts of calendar versions.
+pub fn today() -> chrono::NaiveDate {
+    chrono::Local::now().naive_local().date()
+}
@@ -0,0 +1,236 @@
+use chrono::{TimeZone, Utc};
+use error_chain::bail;
+
+use super::{PreReleaseTemplate, PreReleaseValues, TemplateToken, Version};
+use crate::errors::ErrorKind;
+use crate::CromResult;
+
+impl PreReleaseTemplate {
+    /// Parse a template like `{next}-beta+{sha:12}`.
+    ///
+    /// The template must start with `{next}`, followed by either a `-` for
+    /// a pre-release or a `+` for build metadata. The variables that can be
+    /// used after that are `{sha}` (7 characters), `{sha:N}`, `{branch}`,
+    /// `{timestamp}` and `{distance}`. Neither the pre-release nor the build
+    /// metadata can be empty, and there can only be one `+`.
+    pub fn parse(template: &str) -> CromResult<Self> {
+        let invalid = ErrorKind::InvalidPreReleaseTemplate;
+
+        let rest = match template.strip_prefix("{next}") {
+            Some(rest) => rest,
+            None => bail!(invalid(format!("'{}' must start with {{next}}", template))),
+        };
+
+        if !rest.starts_with('-') && !rest.starts_with('+') {
+            bail!(invalid(format!(
+                "'{}' must have a '-' or '+' after {{next}}",
+                template
+            )));
+        }
+
+        let (pre_release, build) = match rest.split_once('+') {
+            Some((pre_release, build)) => (pre_release, Some(build)),
+            None => (rest, None),
+        };
+        if pre_release == "-" {
+            bail!(invalid(format!("'{}' has an empty pre-release", template)));
+        }
+        match build {
+            Some("") => bail!(invalid(format!("'{}' has empty build metadata", template))),
+            Some(build) if build.contains('+') => {
+                bail!(invalid(format!("'{}' can only have one '+'", template)))
+            }
+            _ => {}
+        }
+
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = rest.chars();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => bail!(invalid(format!("'{}' has an unclosed '{{'", template))),
+                        }
+                    }
+
+                    let token = match parse_variable(&name) {
+                        Some(token) => token,
+                        None => bail!(invalid(format!(
+                            "'{}' has unknown variable {{{}}}",
+                            template, name
+                        ))),
+                    };
+
+                    if !literal.is_empty() {
+                        tokens.push(TemplateToken::Literal(literal.clone()));
+                        literal.clear();
+                    }
+                    tokens.push(token);
+                }
+                c if c.is_ascii_alphanumeric() || c == '-' || c == '.' || c == '+' => {
+                    literal.push(c)
+                }
+                c => bail!(invalid(format!("'{}' can not contain '{}'", template, c))),
+            }
+        }
+
+        if !literal.is_empty() {
+            tokens.push(TemplateToken::Literal(literal));
+        }
+
+        Ok(PreReleaseTemplate { tokens })
+    }
+
+    pub fn render(&self, next: &Version, values: &PreReleaseValues) -> Version {
+        let mut suffix = String::new();
+        for token in &self.tokens {
+            match token {
+                TemplateToken::Literal(text) => suffix.push_str(text),
+                TemplateToken::Sha(length) => {
+                    suffix.extend(values.sha.chars().take(*length));
+                }
+                TemplateToken::Branch => suffix.push_str(&sanitize(&values.branch)),
+                TemplateToken::Distance => suffix.push_str(&values.distance.to_string()),
+                TemplateToken::Timestamp => {
+                    if let Some(time) = Utc.timestamp_opt(values.timestamp, 0).single() {
+                        suffix.push_str(&time.format("%Y%m%d%H%M%S").to_string());
+                    }
+                }
+            }
+        }
+
+        if let Some(build) = suffix.strip_prefix('+') {
+            return next.with_metadata(None, Some(build.to_string()));
+        }
+
+        let suffix = &suffix[1..];
+        match suffix.split_once('+') {
+            Some((pre_release, build)) => {
+                next.with_metadata(Some(pre_release.to_string()), Some(build.to_string()))
+            }
+            None => next.with_metadata(Some(suffix.to_string()), None),
+        }
+    }
+}
+
+impl Default for PreReleaseTemplate {
+    fn default() -> Self {
+        PreReleaseTemplate::parse(crate::statics::DEFAULT_PRE_RELEASE_TEMPLATE)
+            .expect("default pre-release template should be valid")
+    }
+}
+
+fn parse_variable(name: &str) -> Option<TemplateToken> {
+    match name.split_once(':') {
+        None => match name {
+            "sha" => Some(TemplateToken::Sha(7)),
+            "branch" => Some(TemplateToken::Branch),
+            "timestamp" => Some(TemplateToken::Timestamp),
+            "distance" => Some(TemplateToken::Distance),
+            _ => None,
+        },
+        Some(("sha", length)) => match length.parse::<usize>() {
+            Ok(length) if (1..=40).contains(&length) => Some(TemplateToken::Sha(length)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Replace anything that isn't allowed in a SemVer identifier with `-`.
+fn sanitize(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn render_template(template: &str) -> String {
+    let next = super::VersionMatcher::new("v1.2.%d")
+        .match_version(s!("v1.2.4"))
+        .unwrap();
+    let values = PreReleaseValues {
+        sha: s!("0cc81e3b5d1f9a2e7c4b8d6f0a1e3c5b7d9f2a4c"),
+        branch: s!("feature/new_thing"),
+        timestamp: 1_792_305_000,
+        distance: 3,
+    };
+
+    PreReleaseTemplate::parse(template)
+        .unwrap()
+        .render(&next, &values)
+        .to_string()
+}
+
+#[test]
+fn test_render_pre_release() {
+    assert_eq!("v1.2.4-0cc81e3", render_template("{next}-{sha}"));
+    assert_eq!("v1.2.4-SNAPSHOT", render_template("{next}-SNAPSHOT"));
+    assert_eq!(
+        "v1.2.4-beta+0cc81e3b5d1f",
+        render_template("{next}-beta+{sha:12}")
+    );
+    assert_eq!(
+        "v1.2.4-feature-new-thing.20261018063000",
+        render_template("{next}-{branch}.{timestamp}")
+    );
+    assert_eq!("v1.2.4+0cc81e3", render_template("{next}+{sha}"));
+    assert_eq!(
+        "v1.2.4-3.g0cc81e3",
+        render_template("{next}-{distance}.g{sha}")
+    );
+    assert_eq!(
+        "v1.2.4-0cc81e3",
+        PreReleaseTemplate::default()
+            .render(
+                &Version::from(s!("v1.2.4")),
+                &PreReleaseValues {
+                    sha: s!("0cc81e3b5d1f"),
+                    branch: s!("main"),
+                    timestamp: 0,
+                    distance: 0,
+                }
+            )
+            .to_string()
+    );
+}
+
+#[test]
+fn test_invalid_pre_release_template() {
+    for template in &[
+        "SNAPSHOT",
+        "{next}SNAPSHOT",
+        "{next}-{foo}",
+        "{next}-{sha:0}",
+        "{next}-{sha:abc}",
+        "{next}-{sha",
+        "{next}-{next}",
+        "{next}-beta 1",
+        "{next}-beta_1",
+        "{next}-",
+        "{next}-+{sha}",
+        "{next}+",
+        "{next}-beta+",
+        "{next}-a+b+c",
+    ] {
+        let err = PreReleaseTemplate::parse(template).unwrap_err();
+        assert!(
+            matches!(err.kind(), ErrorKind::InvalidPreReleaseTemplate(_)),
+            "{} should be invalid",
+            template
+        );
+    }
+}
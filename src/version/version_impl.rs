@@ -1,30 +1,24 @@
+use chrono::{Datelike, NaiveDate};
 use log::warn;
-use std::cmp::{min, Ord, Ordering};
+use std::cmp::{Ord, Ordering};
 use std::fmt::{Display, Formatter};
 
 #[cfg(test)]
 use super::VersionMatcher;
-use super::{Version, VersionComponent};
+use super::{DatePart, Version, VersionBump, VersionComponent};
 
 impl Ord for Version {
+    /// Orders versions following SemVer precedence.
+    ///
+    /// The release part is compared first, with numbers compared as
+    /// numbers. A version with a pre-release sorts below the same version
+    /// without one, and pre-releases are compared by their dot separated
+    /// identifiers. Build metadata (after a `+`) and leading zeros are
+    /// ignored, so `v1.02` is equal to `v1.2`, the same as `PartialEq`.
     fn cmp(&self, other: &Version) -> Ordering {
-        let end = min(self.parts.len(), other.parts.len());
-        for i in 0..end {
-            let other_part = &other.parts[i];
-            let self_part = &self.parts[i];
-
-            match other_part.cmp(self_part) {
-                Ordering::Equal => continue,
-                Ordering::Less => return Ordering::Greater,
-                Ordering::Greater => return Ordering::Less,
-            }
-        }
-
-        match other.parts.len().cmp(&self.parts.len()) {
-            Ordering::Equal => Ordering::Equal,
-            Ordering::Greater => Ordering::Less,
-            Ordering::Less => Ordering::Greater,
-        }
+        compare_release(&self.release_string(), &other.release_string()).then_with(|| {
+            compare_pre_release(self.pre_release.as_deref(), other.pre_release.as_deref())
+        })
     }
 }
 
@@ -36,19 +30,15 @@ impl PartialOrd for Version {
 
 impl PartialEq for Version {
     fn eq(&self, other: &Version) -> bool {
-        self.to_string() == other.to_string()
+        self.cmp(other) == Ordering::Equal
     }
 }
 
 impl Ord for VersionComponent {
     fn cmp(&self, other: &VersionComponent) -> Ordering {
         match (self, other) {
-            (VersionComponent::Static(s1), VersionComponent::Static(s2)) => s1.cmp(s2),
-            (VersionComponent::Changing(s1), VersionComponent::Static(s2)) => {
-                s1.to_string().cmp(s2)
-            }
-            (VersionComponent::Static(s1), VersionComponent::Changing(s2)) => s1.cmp(&s!(s2)),
             (VersionComponent::Changing(s1), VersionComponent::Changing(s2)) => s1.cmp(s2),
+            _ => compare_release(&self.to_string(), &other.to_string()),
         }
     }
 }
@@ -61,46 +51,347 @@ impl PartialOrd for VersionComponent {
 
 impl PartialEq for VersionComponent {
     fn eq(&self, other: &VersionComponent) -> bool {
-        match (self, other) {
-            (VersionComponent::Static(s1), VersionComponent::Static(s2)) => s1 == s2,
-            (VersionComponent::Changing(s1), VersionComponent::Static(s2)) => &s1.to_string() == s2,
-            (VersionComponent::Static(s1), VersionComponent::Changing(s2)) => s1 == &s2.to_string(),
-            (VersionComponent::Changing(s1), VersionComponent::Changing(s2)) => s1 == s2,
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Display for VersionComponent {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            VersionComponent::Static(part) => write!(f, "{}", part),
+            VersionComponent::Changing(part) => write!(f, "{}", part),
+            VersionComponent::Date(DatePart::Year, part) => write!(f, "{:04}", part),
+            VersionComponent::Date(_, part) => write!(f, "{:02}", part),
+            VersionComponent::Period(part) => write!(f, "{}", part),
+        }
+    }
+}
+
+/// Split the release into runs of digits and runs of everything else,
+/// so `v1.10` becomes `v`, `1`, `.`, `10`.
+fn release_identifiers(input: &str) -> Vec<&str> {
+    let mut identifiers = Vec::new();
+    let mut start = 0;
+
+    let mut previous_digit = None;
+
+    for (i, c) in input.char_indices() {
+        let is_digit = c.is_ascii_digit();
+        if previous_digit == Some(!is_digit) {
+            identifiers.push(&input[start..i]);
+            start = i;
+        }
+        previous_digit = Some(is_digit);
+    }
+
+    if start < input.len() {
+        identifiers.push(&input[start..]);
+    }
+
+    identifiers
+}
+
+/// Lower case alphanumeric identifiers of a pre-release or build, which is
+/// all PEP 440 allows in a local version.
+fn pep440_identifiers(input: &str) -> Vec<String> {
+    input
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|identifier| !identifier.is_empty())
+        .map(|identifier| identifier.to_string())
+        .collect()
+}
+
+fn is_numeric(identifier: &str) -> bool {
+    !identifier.is_empty() && identifier.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Compare identifiers, numbers are compared by value and always sort
+/// below text.
+fn compare_identifier(left: &str, right: &str) -> Ordering {
+    match (is_numeric(left), is_numeric(right)) {
+        (true, true) => {
+            let left = left.trim_start_matches('0');
+            let right = right.trim_start_matches('0');
+            left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+        }
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => left.cmp(right),
+    }
+}
+
+fn compare_identifiers(left: &[&str], right: &[&str]) -> Ordering {
+    for (l, r) in left.iter().zip(right.iter()) {
+        match compare_identifier(l, r) {
+            Ordering::Equal => continue,
+            ordering => return ordering,
+        }
+    }
+
+    left.len().cmp(&right.len())
+}
+
+fn compare_release(left: &str, right: &str) -> Ordering {
+    compare_identifiers(&release_identifiers(left), &release_identifiers(right))
+}
+
+fn compare_pre_release(left: Option<&str>, right: Option<&str>) -> Ordering {
+    let strip_build = |pre: &str| pre.split('+').next().unwrap_or_default().to_string();
+
+    match (left.map(strip_build), right.map(strip_build)) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(left), Some(right)) => {
+            let left: Vec<&str> = left.split('.').collect();
+            let right: Vec<&str> = right.split('.').collect();
+            compare_identifiers(&left, &right)
         }
     }
 }
 
 impl Version {
     pub fn new(parts: Vec<VersionComponent>, pre_release: Option<String>) -> Version {
-        let has_dynamic_version = parts.clone().into_iter().any(|x| match x {
-            VersionComponent::Changing(_) => true,
-            VersionComponent::Static(_) => false,
-        });
+        let has_dynamic_version = parts
+            .iter()
+            .any(|x| !matches!(x, VersionComponent::Static(_)));
 
         Version {
             parts,
             pre_release,
+            build: None,
             is_only_static: !has_dynamic_version,
         }
     }
 
-    pub fn next_version(&self, pre_release: Option<String>) -> Version {
+    /// Copy of this version with a different pre-release and build metadata.
+    pub fn with_metadata(&self, pre_release: Option<String>, build: Option<String>) -> Version {
+        Version {
+            pre_release,
+            build,
+            ..self.clone()
+        }
+    }
+
+    /// The numbers in the version, from left to right, ignoring pre-release
+    /// and build metadata. `v1.2.3` gives `[1, 2, 3]`.
+    pub fn numbers(&self) -> Vec<u64> {
+        release_identifiers(&self.release_string())
+            .into_iter()
+            .filter(|identifier| is_numeric(identifier))
+            .flat_map(|identifier| identifier.parse())
+            .collect()
+    }
+
+    pub fn pre_release(&self) -> Option<&str> {
+        self.pre_release.as_deref()
+    }
+
+    pub fn build(&self) -> Option<&str> {
+        self.build.as_deref()
+    }
+
+    /// The version without the prefix before the first digit, so
+    /// `server-v1.2.3` becomes `1.2.3`.
+    pub fn stripped(&self) -> String {
+        self.to_string()
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .to_string()
+    }
+
+    /// The version in the PEP 440 format Python packaging expects.
+    ///
+    /// Pre-releases named `alpha`, `beta` or `rc` become `a`, `b` and `rc`,
+    /// and `dev` or `SNAPSHOT` become `.devN`. The short `a`, `b` and `c` are
+    /// only labels with a separate number, like `b.2`. Any other pre-release,
+    /// like a sha, is a `.dev0` with the text kept as a local version, so
+    /// `v1.2.3-abc1234+build.5` becomes `1.2.3.dev0+abc1234.build.5`.
+    pub fn pep440(&self) -> String {
+        let numbers = self.numbers();
+        let mut result = if numbers.is_empty() {
+            s!("0")
+        } else {
+            numbers
+                .iter()
+                .map(|number| number.to_string())
+                .collect::<Vec<_>>()
+                .join(".")
+        };
+
+        let mut local = Vec::new();
+        if let Some(pre_release) = &self.pre_release {
+            let identifiers = pep440_identifiers(pre_release);
+
+            // `rc1` and `rc.1` both have the number 1.
+            let first = identifiers.first().map(String::as_str).unwrap_or_default();
+            let label = first.trim_end_matches(|c: char| c.is_ascii_digit());
+            let (number, used) = match &first[label.len()..] {
+                "" => match identifiers.get(1) {
+                    Some(next) if is_numeric(next) => (next.as_str(), 2),
+                    _ => ("0", 1),
+                },
+                number => (number, 1),
+            };
+
+            // `b123456` is more likely a sha than a beta, so a single letter
+            // is only a label when its number is a separate identifier.
+            let separate_number = used == 2;
+            let label = match label {
+                "alpha" => Some("a"),
+                "beta" => Some("b"),
+                "rc" | "pre" | "preview" => Some("rc"),
+                "dev" | "snapshot" => Some(".dev"),
+                "a" if separate_number => Some("a"),
+                "b" if separate_number => Some("b"),
+                "c" if separate_number => Some("rc"),
+                _ => None,
+            };
+
+            match label {
+                Some(label) => {
+                    let number = number.trim_start_matches('0');
+                    let number = if number.is_empty() { "0" } else { number };
+                    result.push_str(&format!("{}{}", label, number));
+                    local.extend(identifiers.into_iter().skip(used));
+                }
+                None => {
+                    result.push_str(".dev0");
+                    local.extend(identifiers);
+                }
+            }
+        }
+
+        if let Some(build) = &self.build {
+            local.extend(pep440_identifiers(build));
+        }
+        if !local.is_empty() {
+            result.push('+');
+            result.push_str(&local.join("."));
+        }
+
+        result
+    }
+
+    /// Mark the version as built from a working tree with uncommitted changes.
+    ///
+    /// `dirty` is added as the last pre-release identifier, so `v1.2.3`
+    /// becomes `v1.2.3-dirty` and `v1.2.3-abc123` becomes `v1.2.3-abc123.dirty`.
+    pub fn with_dirty_marker(&self) -> Version {
+        let pre_release = match &self.pre_release {
+            Some(pre_release) => format!("{}.dirty", pre_release),
+            None => s!("dirty"),
+        };
+
+        self.with_metadata(Some(pre_release), self.build.clone())
+    }
+
+    /// Returns true if the pattern has a counter for `bump`.
+    ///
+    /// Calendar versions can always take a `Patch` bump, which moves them
+    /// to the current period.
+    pub fn can_bump(&self, bump: VersionBump) -> bool {
+        self.counter_position(bump).is_some() || (bump == VersionBump::Patch && self.is_calendar())
+    }
+
+    /// Increment the counter selected by `bump`, resetting every counter to
+    /// the right of it.
+    ///
+    /// Date parts are set from `today`. When any of them change the
+    /// period counters start over at 0, otherwise they are incremented.
+    pub fn next_version_on(
+        &self,
+        bump: VersionBump,
+        pre_release: Option<String>,
+        today: NaiveDate,
+    ) -> Version {
         if self.is_only_static {
             warn!("Attempting to bump a static only version!");
         }
 
+        let position = self.counter_position(bump);
+        if position.is_none() && !self.is_calendar() {
+            warn!("Version {} has no counter for {:?}", self, bump);
+            return Version::new(self.parts.clone(), pre_release);
+        }
+
+        let iso_week = self
+            .parts
+            .iter()
+            .any(|x| matches!(x, VersionComponent::Date(DatePart::Week, _)));
+        let new_period = self.parts.iter().any(|x| match x {
+            VersionComponent::Date(part, value) => *value != part.value_on(today, iso_week),
+            _ => false,
+        });
+
         let parts: Vec<VersionComponent> = self
             .parts
             .clone()
             .into_iter()
-            .map(|x| match x {
-                VersionComponent::Static(part) => VersionComponent::Static(part),
-                VersionComponent::Changing(part) => VersionComponent::Changing(part + 1),
+            .enumerate()
+            .map(|(i, x)| match x {
+                VersionComponent::Changing(part) if Some(i) == position => {
+                    VersionComponent::Changing(part + 1)
+                }
+                VersionComponent::Changing(_) if matches!(position, Some(p) if i > p) => {
+                    VersionComponent::Changing(0)
+                }
+                VersionComponent::Date(part, _) => {
+                    VersionComponent::Date(part, part.value_on(today, iso_week))
+                }
+                VersionComponent::Period(_) if new_period => VersionComponent::Period(0),
+                VersionComponent::Period(part) if position.is_none() => {
+                    VersionComponent::Period(part + 1)
+                }
+                x => x,
             })
             .collect();
 
         Version::new(parts, pre_release)
     }
+
+    fn is_calendar(&self) -> bool {
+        self.parts.iter().any(|x| {
+            matches!(
+                x,
+                VersionComponent::Date(_, _) | VersionComponent::Period(_)
+            )
+        })
+    }
+
+    /// Index into `parts` of the counter for `bump`, counters are counted
+    /// from the right.
+    fn counter_position(&self, bump: VersionBump) -> Option<usize> {
+        let offset = match bump {
+            VersionBump::Patch => 0,
+            VersionBump::Minor => 1,
+            VersionBump::Major => 2,
+        };
+
+        self.parts
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| matches!(x, VersionComponent::Changing(_)))
+            .map(|(i, _)| i)
+            .rev()
+            .nth(offset)
+    }
+
+    fn release_string(&self) -> String {
+        self.parts.iter().map(|x| x.to_string()).collect()
+    }
+}
+
+impl DatePart {
+    fn value_on(&self, date: NaiveDate, iso_week: bool) -> i32 {
+        match self {
+            DatePart::Year if iso_week => date.iso_week().year(),
+            DatePart::Year => date.year(),
+            DatePart::Month => date.month() as i32,
+            DatePart::Week => date.iso_week().week() as i32,
+            DatePart::Day => date.day() as i32,
+        }
+    }
 }
 
 impl From<String> for Version {
@@ -111,33 +402,89 @@ impl From<String> for Version {
 
 impl Display for Version {
     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
-        let parts: Vec<String> = self
-            .parts
-            .clone()
-            .into_iter()
-            .map(|x| match x {
-                VersionComponent::Static(part) => part,
-                VersionComponent::Changing(part) => format!("{}", part),
-            })
-            .collect();
-
-        let joined = parts.join(".");
         let pre_release = match &self.pre_release {
             Some(x) => format!("-{}", x),
             None => "".to_string(),
         };
+        let build = match &self.build {
+            Some(x) => format!("+{}", x),
+            None => "".to_string(),
+        };
 
-        write!(f, "{}{}", joined, pre_release)
+        write!(f, "{}{}{}", self.release_string(), pre_release, build)
     }
 }
 
+#[test]
+fn test_pep440() {
+    let version = |text: &str| {
+        let (release, build) = match text.split_once('+') {
+            Some((release, build)) => (release, Some(s!(build))),
+            None => (text, None),
+        };
+        let (release, pre_release) = match release.split_once('-') {
+            Some((release, pre_release)) => (release, Some(s!(pre_release))),
+            None => (release, None),
+        };
+        Version::from(s!(release)).with_metadata(pre_release, build)
+    };
+
+    assert_eq!("1.2.3", version("v1.2.3").pep440());
+    assert_eq!("2024.5.1", version("2024.05.1").pep440());
+    assert_eq!("1.0.0rc1", version("1.0.0-rc.1").pep440());
+    assert_eq!("1.0.0b2", version("v1.0.0-beta2").pep440());
+    assert_eq!("1.0.0a0", version("1.0.0-alpha").pep440());
+    assert_eq!("1.0.0.dev0", version("1.0.0-SNAPSHOT").pep440());
+    assert_eq!("1.0.0rc1+dirty", version("1.0.0-rc.1.dirty").pep440());
+    assert_eq!(
+        "1.2.4.dev0+abc1234.build.5",
+        version("v1.2.4-abc1234+build.5").pep440()
+    );
+    assert_eq!("1.2.4.dev0+main.3", version("v1.2.4-main_3").pep440());
+    assert_eq!("1.2.4b2", version("v1.2.4-b.2").pep440());
+    assert_eq!("1.2.4.dev0+b123456", version("v1.2.4-b123456").pep440());
+    assert_eq!("1.2.4.dev0+a904113", version("v1.2.4-a904113").pep440());
+    assert_eq!("1.2.4.dev0+c", version("v1.2.4-c").pep440());
+}
+
 #[test]
 fn test_next_version() {
     let matcher = VersionMatcher::new("1.2.3.%d");
     let version = matcher.match_version(s!("1.2.3.5")).unwrap();
 
     assert_eq!("1.2.3.5", version.to_string());
-    assert_eq!("1.2.3.6", version.next_version(None).to_string());
+    assert_eq!(
+        "1.2.3.6",
+        version
+            .next_version_on(VersionBump::Patch, None, super::today())
+            .to_string()
+    );
+}
+
+#[test]
+fn test_next_version_bump() {
+    let matcher = VersionMatcher::new("%d.%d.%d");
+    let version = matcher.match_version(s!("1.4.7")).unwrap();
+
+    let next = |bump| {
+        version
+            .next_version_on(bump, None, super::today())
+            .to_string()
+    };
+    assert_eq!("1.4.8", next(VersionBump::Patch));
+    assert_eq!("1.5.0", next(VersionBump::Minor));
+    assert_eq!("2.0.0", next(VersionBump::Major));
+
+    let matcher = VersionMatcher::new("v1.%d.%d");
+    let version = matcher.match_version(s!("v1.4.7")).unwrap();
+    assert!(version.can_bump(VersionBump::Minor));
+    assert!(!version.can_bump(VersionBump::Major));
+    assert_eq!(
+        "v1.5.0",
+        version
+            .next_version_on(VersionBump::Minor, None, super::today())
+            .to_string()
+    );
 }
 
 #[test]
@@ -192,3 +539,159 @@ fn test_version_order_10() {
     assert_eq!(v[1].to_string(), "1.2.9");
     assert_eq!(v[2].to_string(), "1.2.10");
 }
+
+#[test]
+fn test_pre_release_precedence() {
+    let matcher = VersionMatcher::new("1.2.%d");
+    let release = matcher.match_version(s!("1.2.4")).unwrap();
+    let previous = matcher.match_version(s!("1.2.3")).unwrap();
+    let pre_release = previous.next_version_on(VersionBump::Patch, Some(s!("abc")), super::today());
+
+    assert_eq!("1.2.4-abc", pre_release.to_string());
+    assert!(pre_release < release);
+    assert!(previous < pre_release);
+    assert_ne!(pre_release, release);
+    assert_eq!(Ordering::Less, pre_release.cmp(&release));
+}
+
+#[test]
+fn test_pre_release_identifiers() {
+    let matcher = VersionMatcher::new("1.0.%d");
+    let base = matcher.match_version(s!("1.0.0")).unwrap();
+    let with_pre = |pre: &str| Version::new(base.parts.clone(), Some(pre.to_string()));
+
+    // Example from https://semver.org/#spec-item-11
+    let expected = vec![
+        with_pre("alpha"),
+        with_pre("alpha.1"),
+        with_pre("alpha.beta"),
+        with_pre("beta"),
+        with_pre("beta.2"),
+        with_pre("beta.11"),
+        with_pre("rc.1"),
+        base.clone(),
+    ];
+
+    let mut versions = expected.clone();
+    versions.reverse();
+    versions.sort();
+
+    let expected: Vec<String> = expected.iter().map(|x| x.to_string()).collect();
+    let versions: Vec<String> = versions.iter().map(|x| x.to_string()).collect();
+    assert_eq!(expected, versions);
+
+    assert_eq!(with_pre("beta.2+abc"), with_pre("beta.2+def"));
+    assert_eq!(
+        Ordering::Equal,
+        with_pre("beta.2+abc").cmp(&with_pre("beta.2+def"))
+    );
+}
+
+#[test]
+fn test_leading_zeros_are_equal() {
+    let zero = Version::new(vec![VersionComponent::Static(s!("v1.02"))], None);
+    let plain = Version::new(vec![VersionComponent::Static(s!("v1.2"))], None);
+
+    assert_eq!(zero, plain);
+    assert_eq!(Ordering::Equal, zero.cmp(&plain));
+    assert!(zero < Version::new(vec![VersionComponent::Static(s!("v1.3"))], None));
+}
+
+#[test]
+fn test_static_and_changing_compare_numerically() {
+    let fixed = Version::new(
+        vec![
+            VersionComponent::Static(s!("1.")),
+            VersionComponent::Static(s!("10")),
+        ],
+        None,
+    );
+    let matcher = VersionMatcher::new("1.%d");
+    let changing = matcher.match_version(s!("1.9")).unwrap();
+
+    assert!(changing < fixed);
+    assert!(VersionComponent::Changing(9) < VersionComponent::Static(s!("10")));
+    assert_eq!(
+        VersionComponent::Changing(10),
+        VersionComponent::Static(s!("10"))
+    );
+    assert_eq!(
+        Version::from(s!("1.10")),
+        matcher.match_version(s!("1.10")).unwrap()
+    );
+}
+
+#[test]
+fn test_next_calendar_version() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let matcher = VersionMatcher::new("%Y.%m.%n");
+
+    let first = matcher.build_default_version().next_version_on(
+        VersionBump::Patch,
+        None,
+        date(2026, 10, 18),
+    );
+    assert_eq!("2026.10.0", first.to_string());
+
+    let second = first.next_version_on(VersionBump::Patch, None, date(2026, 10, 20));
+    assert_eq!("2026.10.1", second.to_string());
+
+    let third = second.next_version_on(VersionBump::Patch, None, date(2026, 11, 2));
+    assert_eq!("2026.11.0", third.to_string());
+
+    assert!(first < second);
+    assert!(second < third);
+}
+
+#[test]
+fn test_next_calendar_version_iso_week() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let matcher = VersionMatcher::new("v%Y.%W.%D-%n");
+    let version = matcher.match_version(s!("v2026.53.31-0")).unwrap();
+
+    // 2027-01-01 is still in the last ISO week of 2026
+    assert_eq!(
+        "v2026.53.01-0",
+        version
+            .next_version_on(VersionBump::Patch, None, date(2027, 1, 1))
+            .to_string()
+    );
+
+    let matcher = VersionMatcher::new("%Y.%m.%d");
+    let version = matcher.match_version(s!("2026.10.4")).unwrap();
+    assert_eq!(
+        "2026.11.5",
+        version
+            .next_version_on(VersionBump::Patch, None, date(2026, 11, 2))
+            .to_string()
+    );
+}
+
+#[test]
+fn test_dirty_marker() {
+    let matcher = VersionMatcher::new("v1.2.%d");
+    let version = matcher.match_version(s!("v1.2.3")).unwrap();
+
+    assert_eq!("v1.2.3-dirty", version.with_dirty_marker().to_string());
+    assert_eq!(
+        "v1.2.4-abc123.dirty",
+        version
+            .next_version_on(VersionBump::Patch, Some(s!("abc123")), super::today())
+            .with_dirty_marker()
+            .to_string()
+    );
+}
+
+#[test]
+fn test_version_details() {
+    let matcher = VersionMatcher::new("server-v1.%d.%d");
+    let version = matcher
+        .match_version(s!("server-v1.2.3"))
+        .unwrap()
+        .with_metadata(Some(s!("beta.1")), Some(s!("abc123")));
+
+    assert_eq!(vec![1, 2, 3], version.numbers());
+    assert_eq!(Some("beta.1"), version.pre_release());
+    assert_eq!(Some("abc123"), version.build());
+    assert_eq!("1.2.3-beta.1+abc123", version.stripped());
+}
@@ -1,17 +1,40 @@
+use regex::Regex;
+
 use super::*;
 
 impl VersionMatcher {
+    /// Build a matcher from a pattern like `v1.2-%d`.
+    ///
+    /// Every `%d` is a counter, everything else is matched literally. Use
+    /// `%%` for a literal `%`. Calendar versions use `%Y` (year), `%m`
+    /// (month), `%W` (ISO week), `%D` (day) and `%n`, a counter that
+    /// starts over each period.
     pub fn new(pattern: &str) -> Self {
-        let split = pattern.split('.');
-        let parts: Vec<VersionComponent> = split
-            .into_iter()
-            .map(|x| match x {
-                "%d" => VersionComponent::Changing(0),
-                _ => VersionComponent::Static(x.to_string()),
-            })
-            .collect();
-
-        VersionMatcher { pattern: parts }
+        let parts = parse_pattern(pattern);
+
+        let mut regex = String::from("^");
+        for part in &parts {
+            match part {
+                VersionComponent::Static(value) => regex.push_str(&regex::escape(value)),
+                VersionComponent::Changing(_) | VersionComponent::Period(_) => {
+                    regex.push_str("(0|[1-9][0-9]*)")
+                }
+                VersionComponent::Date(DatePart::Year, _) => regex.push_str("([0-9]{4})"),
+                VersionComponent::Date(DatePart::Month, _) => regex.push_str("(0[1-9]|1[0-2])"),
+                VersionComponent::Date(DatePart::Week, _) => {
+                    regex.push_str("(0[1-9]|[1-4][0-9]|5[0-3])")
+                }
+                VersionComponent::Date(DatePart::Day, _) => {
+                    regex.push_str("(0[1-9]|[12][0-9]|3[01])")
+                }
+            }
+        }
+        regex.push('$');
+
+        VersionMatcher {
+            pattern: parts,
+            regex: Regex::new(&regex).expect("escaped pattern should be a valid regex"),
+        }
     }
 
     pub fn build_default_version(&self) -> Version {
@@ -19,31 +42,26 @@ impl VersionMatcher {
     }
 
     pub fn match_version(&self, input: String) -> Option<Version> {
-        let split: Vec<&str> = input.trim().split('.').collect();
-
-        if split.len() != self.pattern.len() {
-            return None;
-        }
+        let captures = self.regex.captures(input.trim())?;
+        let mut counters = captures.iter().skip(1);
 
         let mut version_parts: Vec<VersionComponent> = Vec::new();
-
-        for (i, split_part) in split.iter().enumerate() {
-            let pattern_part = &self.pattern[i];
-
+        for pattern_part in &self.pattern {
             match pattern_part {
                 VersionComponent::Static(value) => {
-                    if value != split_part {
-                        return None;
-                    }
                     version_parts.push(VersionComponent::Static(value.to_string()));
                 }
-                VersionComponent::Changing(_) => {
-                    let parsed = match split_part.parse::<i32>() {
+                dynamic => {
+                    let parsed = match counters.next()??.as_str().parse::<i32>() {
                         Err(_) => return None,
                         Ok(v) => v,
                     };
 
-                    version_parts.push(VersionComponent::Changing(parsed));
+                    version_parts.push(match dynamic {
+                        VersionComponent::Date(part, _) => VersionComponent::Date(*part, parsed),
+                        VersionComponent::Period(_) => VersionComponent::Period(parsed),
+                        _ => VersionComponent::Changing(parsed),
+                    });
                 }
             }
         }
@@ -52,6 +70,52 @@ impl VersionMatcher {
     }
 }
 
+fn parse_pattern(pattern: &str) -> Vec<VersionComponent> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            match chars.peek() {
+                Some('%') => {
+                    chars.next();
+                }
+                Some(token) => {
+                    let component = match token {
+                        'd' => Some(VersionComponent::Changing(0)),
+                        'n' => Some(VersionComponent::Period(0)),
+                        'Y' => Some(VersionComponent::Date(DatePart::Year, 0)),
+                        'm' => Some(VersionComponent::Date(DatePart::Month, 0)),
+                        'W' => Some(VersionComponent::Date(DatePart::Week, 0)),
+                        'D' => Some(VersionComponent::Date(DatePart::Day, 0)),
+                        _ => None,
+                    };
+
+                    if let Some(component) = component {
+                        chars.next();
+                        if !literal.is_empty() {
+                            parts.push(VersionComponent::Static(literal.clone()));
+                            literal.clear();
+                        }
+                        parts.push(component);
+                        continue;
+                    }
+                }
+                None => {}
+            }
+        }
+
+        literal.push(c);
+    }
+
+    if !literal.is_empty() {
+        parts.push(VersionComponent::Static(literal));
+    }
+
+    parts
+}
+
 #[test]
 fn parse_semver() {
     let matcher = VersionMatcher::new("1.2.%d");
@@ -81,3 +145,70 @@ fn parse_random() {
 
     assert_eq!(None, matcher.match_version(s!("2.2.3")));
 }
+
+#[test]
+fn parse_separators() {
+    for (pattern, tag, next) in &[
+        ("release-%d", "release-5", "release-6"),
+        ("v1.2-%d", "v1.2-9", "v1.2-10"),
+        ("2024_%d", "2024_0", "2024_1"),
+        ("build%d", "build41", "build42"),
+        ("%d", "7", "8"),
+        ("v%d.%d-rc%d", "v1.4-rc2", "v1.4-rc3"),
+        ("100%%-%d", "100%-1", "100%-2"),
+    ] {
+        let matcher = VersionMatcher::new(pattern);
+        let version = matcher.match_version(s!(tag)).unwrap();
+
+        assert_eq!(s!(tag), version.to_string());
+        assert_eq!(
+            s!(next),
+            version
+                .next_version_on(VersionBump::Patch, None, super::today())
+                .to_string()
+        );
+    }
+}
+
+#[test]
+fn parse_separators_mismatch() {
+    let matcher = VersionMatcher::new("release-%d");
+
+    assert_eq!(None, matcher.match_version(s!("release-")));
+    assert_eq!(None, matcher.match_version(s!("release-05")));
+    assert_eq!(None, matcher.match_version(s!("release-5-hotfix")));
+    assert_eq!(None, matcher.match_version(s!("prerelease-5")));
+    assert_eq!(None, matcher.match_version(s!("release.5")));
+
+    let matcher = VersionMatcher::new("v1.2.%d");
+    assert_eq!(None, matcher.match_version(s!("v1x2x3")));
+}
+
+#[test]
+fn default_version_matches_pattern() {
+    let matcher = VersionMatcher::new("v1.2-%d");
+
+    assert_eq!("v1.2-0", matcher.build_default_version().to_string());
+}
+
+#[test]
+fn parse_calendar() {
+    let matcher = VersionMatcher::new("%Y.%m.%n");
+
+    let version = matcher.match_version(s!("2026.01.3")).unwrap();
+    assert_eq!("2026.01.3", version.to_string());
+
+    assert_eq!(None, matcher.match_version(s!("2026.13.0")));
+    assert_eq!(None, matcher.match_version(s!("2026.1.0")));
+    assert_eq!(None, matcher.match_version(s!("26.01.0")));
+
+    let matcher = VersionMatcher::new("v%Y-W%W.%D");
+    assert_eq!(
+        "v2026-W07.31",
+        matcher
+            .match_version(s!("v2026-W07.31"))
+            .unwrap()
+            .to_string()
+    );
+    assert_eq!(None, matcher.match_version(s!("v2026-W54.01")));
+}